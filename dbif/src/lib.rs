@@ -1,4 +1,4 @@
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, Connection};
 use std::error::Error;
 use std::fmt;
 use serde::{Deserialize, Serialize};
@@ -12,6 +12,8 @@ pub struct ActorRecord {
     pub pem_private_key: String,
     pub pem_public_key: String,
     pub last_episode_guid: String,
+    pub last_episode_published: u64,
+    pub feed_url: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -36,6 +38,61 @@ pub struct ReplyRecord {
     pub published: String,
     pub received: u64,
     pub conversation: String,
+    pub edited: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteKeyRecord {
+    pub keyid: String,
+    pub owner: String,
+    pub pem_public_key: String,
+    pub fetched: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReactionRecord {
+    pub pcid: u64,
+    pub statusid: String,
+    pub actor: String,
+    pub activityid: String,
+    pub received: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeliveryRecord {
+    pub id: u64,
+    pub pcid: u64,
+    pub inbox: String,
+    pub activity: String,
+    pub attempts: u64,
+    pub next_attempt: u64,
+    pub created: u64,
+    pub status: String,
+    pub last_error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NoteRecord {
+    pub id: u64,
+    pub pcid: u64,
+    pub content: String,
+    pub in_reply_to: String,
+    pub published: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SeenEpisodeRecord {
+    pub pcid: u64,
+    pub guid: String,
+    pub published: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiCacheRecord {
+    pub key: String,
+    pub body: String,
+    pub not_found: u64,
+    pub expires: u64,
 }
 
 #[derive(Debug)]
@@ -92,6 +149,19 @@ fn set_database_file_permissions(filepath: &str) -> Result<bool, Box<dyn Error>>
 }
 
 
+//Add a column to an existing table if an older version of the schema created it without one
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool, Box<dyn Error>> {
+    let mut stmt = conn.prepare(format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1", table).as_str())?;
+    let count: u64 = stmt.query_row(params![column], |row| row.get(0))?;
+    if count > 0 {
+        return Ok(false);
+    }
+
+    conn.execute(format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition).as_str(), [])?;
+    Ok(true)
+}
+
+
 //Create or update a new database file if needed
 pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
     let conn = connect_to_database(true, filepath)?;
@@ -103,7 +173,9 @@ pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
              guid text,
              pem_private_key text,
              pem_public_key text,
-             last_episode_guid text
+             last_episode_guid text,
+             last_episode_published integer default 0,
+             feed_url text default ''
          )",
         [],
     ) {
@@ -116,6 +188,24 @@ pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
         }
     }
 
+    //Actors tables from before every new episode was announced need the high water mark column
+    match add_column_if_missing(&conn, "actors", "last_episode_published", "integer default 0") {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add last_episode_published column to actors table: [{}].", filepath).into())));
+        }
+    }
+
+    //Podpings name feeds by url, so actors need to know theirs
+    match add_column_if_missing(&conn, "actors", "feed_url", "text default ''") {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add feed_url column to actors table: [{}].", filepath).into())));
+        }
+    }
+
     //Create indexes on the actors table
     match conn.execute(
         "CREATE INDEX IF NOT EXISTS guid_idx ON actors (guid)",
@@ -129,6 +219,18 @@ pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
             return Err(Box::new(HydraError(format!("Failed to create database actors indexes: [{}].", filepath).into())));
         }
     }
+    match conn.execute(
+        "CREATE INDEX IF NOT EXISTS feed_url_idx ON actors (feed_url)",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Actors indexes created.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database actors indexes: [{}].", filepath).into())));
+        }
+    }
 
     //Create the followers table
     match conn.execute(
@@ -216,7 +318,8 @@ pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
              sensitive integer,
              published text,
              received integer,
-             conversation text
+             conversation text,
+             edited integer default 0
          )",
         [],
     ) {
@@ -229,6 +332,15 @@ pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
         }
     }
 
+    //Replies tables from before edits were tracked need the edited column
+    match add_column_if_missing(&conn, "replies", "edited", "integer default 0") {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add edited column to replies table: [{}].", filepath).into())));
+        }
+    }
+
     match conn.execute(
         "CREATE INDEX IF NOT EXISTS pcid_idx ON replies (pcid)",
         [],
@@ -294,6 +406,173 @@ pub fn create_database(filepath: &String) -> Result<bool, Box<dyn Error>> {
         }
     }
 
+    //Create the likes and shares tables, which hold the same reaction rows
+    for table in ["likes", "shares"] {
+        match conn.execute(
+            format!("CREATE TABLE IF NOT EXISTS {} (
+                 pcid integer,
+                 statusid text,
+                 actor text,
+                 activityid text,
+                 received integer
+             )", table).as_str(),
+            [],
+        ) {
+            Ok(_) => {
+                println!("Table: [{}] is ready.", table);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return Err(Box::new(HydraError(format!("Failed to create database {} table: [{}].", table, filepath).into())));
+            }
+        }
+
+        match conn.execute(
+            format!("CREATE UNIQUE INDEX IF NOT EXISTS {}_pcid_statusid_actor_idx ON {} (pcid, statusid, actor)", table, table).as_str(),
+            [],
+        ) {
+            Ok(_) => {
+                println!("Table: [{}] index created.", table);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                return Err(Box::new(HydraError(format!("Failed to create database {} index: [{}].", table, filepath).into())));
+            }
+        }
+    }
+
+    //Create the remote keys table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS remote_keys (
+             keyid text primary key,
+             owner text,
+             pem_public_key text,
+             fetched integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Remote keys table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database remote keys table: [{}].", filepath).into())));
+        }
+    }
+
+    //Create the outbound deliveries table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS deliveries (
+             id integer primary key autoincrement,
+             pcid integer,
+             inbox text,
+             activity text,
+             attempts integer,
+             next_attempt integer,
+             created integer,
+             status text,
+             last_error text
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Deliveries table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database deliveries table: [{}].", filepath).into())));
+        }
+    }
+
+    //Create indexes on the deliveries table
+    match conn.execute(
+        "CREATE INDEX IF NOT EXISTS status_next_attempt_idx ON deliveries (status, next_attempt)",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Deliveries index created.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database deliveries index: [{}].", filepath).into())));
+        }
+    }
+
+    //Create the authored notes table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS notes (
+             id integer primary key autoincrement,
+             pcid integer,
+             content text,
+             in_reply_to text,
+             published integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Notes table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database notes table: [{}].", filepath).into())));
+        }
+    }
+
+    //Create the table of episodes already announced at the high water mark
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS seen_episodes (
+             pcid integer,
+             guid text,
+             published integer,
+             primary key (pcid, guid)
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Seen episodes table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database seen episodes table: [{}].", filepath).into())));
+        }
+    }
+
+    //Create the table of small pieces of running state, like how far along the podping stream we've read
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_state (
+             name text primary key,
+             value integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("App state table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database app state table: [{}].", filepath).into())));
+        }
+    }
+
+    //Create the podcast index api cache table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS api_cache (
+             key text primary key,
+             body text,
+             not_found integer,
+             expires integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Api cache table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to create database api cache table: [{}].", filepath).into())));
+        }
+    }
+
     Ok(true)
 }
 
@@ -307,15 +586,19 @@ pub fn add_actor_to_db(filepath: &String, actor: ActorRecord) -> Result<bool, Bo
                                       guid, \
                                       pem_private_key, \
                                       pem_public_key, \
-                                      last_episode_guid \
+                                      last_episode_guid, \
+                                      last_episode_published, \
+                                      feed_url \
                                     ) \
-                        VALUES (?1, ?2, ?3, ?4, ?5)",
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
                        params![
                            actor.pcid,
                            actor.guid,
                            actor.pem_private_key,
                            actor.pem_public_key,
-                           actor.last_episode_guid
+                           actor.last_episode_guid,
+                           actor.last_episode_published,
+                           actor.feed_url
                        ],
     ) {
         Ok(_) => {
@@ -349,6 +632,49 @@ pub fn update_actor_last_episode_guid_in_db(filepath: &String, pcid: u64, episod
     }
 }
 
+pub fn update_actor_last_episode_published_in_db(filepath: &String, pcid: u64, published: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("UPDATE actors \
+                            SET last_episode_published = ?1 \
+                            WHERE pcid = ?2",
+                       params![
+                           published,
+                           pcid,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to update published: [{}] for pcid: [{}].", published, pcid).into())));
+        }
+    }
+}
+
+pub fn update_actor_feed_url_in_db(filepath: &String, pcid: u64, feed_url: String) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("UPDATE actors \
+                            SET feed_url = ?1 \
+                            WHERE pcid = ?2 \
+                              AND IFNULL(feed_url, '') != ?1",
+                       params![
+                           feed_url,
+                           pcid,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to update feed url: [{}] for pcid: [{}].", feed_url, pcid).into())));
+        }
+    }
+}
+
 pub fn get_actor_from_db(filepath: &String, pcid: u64) -> Result<ActorRecord, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
     let mut actors: Vec<ActorRecord> = Vec::new();
@@ -360,7 +686,9 @@ pub fn get_actor_from_db(filepath: &String, pcid: u64) -> Result<ActorRecord, Bo
                                     guid,\
                                     pem_private_key, \
                                     pem_public_key, \
-                                    last_episode_guid \
+                                    last_episode_guid, \
+                                    IFNULL(last_episode_published, 0), \
+                                    IFNULL(feed_url, '') \
                                  FROM actors \
                                  WHERE pcid = :pcid \
                                  ORDER BY pcid DESC \
@@ -372,6 +700,8 @@ pub fn get_actor_from_db(filepath: &String, pcid: u64) -> Result<ActorRecord, Bo
             pem_private_key: row.get(2)?,
             pem_public_key: row.get(3)?,
             last_episode_guid: row.get(4)?,
+            last_episode_published: row.get(5)?,
+            feed_url: row.get(6)?,
         })
     }).unwrap();
 
@@ -389,6 +719,48 @@ pub fn get_actor_from_db(filepath: &String, pcid: u64) -> Result<ActorRecord, Bo
     Err(Box::new(HydraError(format!("Failed to get actor: [{}].", pcid).into())))
 }
 
+pub fn get_actor_from_db_by_feed_url(filepath: &String, feed_url: String) -> Result<ActorRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut actors: Vec<ActorRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    pcid, \
+                                    guid,\
+                                    pem_private_key, \
+                                    pem_public_key, \
+                                    last_episode_guid, \
+                                    IFNULL(last_episode_published, 0), \
+                                    IFNULL(feed_url, '') \
+                                 FROM actors \
+                                 WHERE feed_url = ?1 \
+                                   AND pcid > 0 \
+                                 LIMIT 1")?;
+    let rows = stmt.query_map(params![feed_url], |row| {
+        Ok(ActorRecord {
+            pcid: row.get(0)?,
+            guid: row.get(1)?,
+            pem_private_key: row.get(2)?,
+            pem_public_key: row.get(3)?,
+            last_episode_guid: row.get(4)?,
+            last_episode_published: row.get(5)?,
+            feed_url: row.get(6)?,
+        })
+    })?;
+
+    //Parse the results
+    for row in rows {
+        let actor: ActorRecord = row?;
+        actors.push(actor);
+    }
+
+    if actors.len() > 0 {
+        return Ok(actors[0].clone());
+    }
+
+    Err(Box::new(HydraError(format!("No actor for feed url: [{}].", feed_url).into())))
+}
+
 pub fn get_actors_from_db(filepath: &String) -> Result<Vec<ActorRecord>, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
     let mut actors: Vec<ActorRecord> = Vec::new();
@@ -400,7 +772,9 @@ pub fn get_actors_from_db(filepath: &String) -> Result<Vec<ActorRecord>, Box<dyn
                                     guid,\
                                     pem_private_key, \
                                     pem_public_key, \
-                                    last_episode_guid \
+                                    last_episode_guid, \
+                                    IFNULL(last_episode_published, 0), \
+                                    IFNULL(feed_url, '') \
                                  FROM actors \
                                  ORDER BY pcid ASC \
                                  LIMIT :max")?;
@@ -411,6 +785,8 @@ pub fn get_actors_from_db(filepath: &String) -> Result<Vec<ActorRecord>, Box<dyn
             pem_private_key: row.get(2)?,
             pem_public_key: row.get(3)?,
             last_episode_guid: row.get(4)?,
+            last_episode_published: row.get(5)?,
+            feed_url: row.get(6)?,
         })
     }).unwrap();
 
@@ -428,6 +804,23 @@ pub fn get_actors_from_db(filepath: &String) -> Result<Vec<ActorRecord>, Box<dyn
     Err(Box::new(HydraError(format!("Failed to get any actors.").into())))
 }
 
+//Podcast actors that have sent anything out to followers since the given time
+pub fn get_active_actor_count_from_db(filepath: &String, since: u64) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    //Prepare and execute the query.  The instance actor (pcid 0) is not a podcast
+    let mut stmt = conn.prepare("SELECT \
+                                    COUNT(DISTINCT pcid) \
+                                 FROM deliveries \
+                                 WHERE pcid > 0 \
+                                   AND created >= ?1")?;
+    let count = stmt.query_row(params![since], |row| {
+        row.get(0)
+    })?;
+
+    return Ok(count);
+}
+
 
 //GetSet a follower in the database
 pub fn add_follower_to_db(filepath: &String, follower: FollowerRecord) -> Result<bool, Box<dyn Error>> {
@@ -480,6 +873,51 @@ pub fn remove_follower_from_db(filepath: &String, follower: FollowerRecord) -> R
     }
 }
 
+//Refresh the delivery details of an actor across every podcast they follow
+pub fn update_follower_inboxes_in_db(filepath: &String, follower: FollowerRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("UPDATE followers \
+                        SET instance = ?1, \
+                            inbox = ?2, \
+                            shared_inbox = ?3 \
+                        WHERE actor = ?4",
+                       params![
+                           follower.instance,
+                           follower.inbox,
+                           follower.shared_inbox,
+                           follower.actor,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to update follower: [{}].", follower.actor).into())));
+        }
+    }
+}
+
+//Drop an actor from the followers of every podcast
+pub fn remove_follower_from_db_by_actor(filepath: &String, actor: String) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("DELETE FROM followers WHERE actor=?1",
+                       params![
+                           actor,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove follower: [{}].", actor).into())));
+        }
+    }
+}
+
 pub fn get_followers_from_db(filepath: &String, pcid: u64) -> Result<Vec<FollowerRecord>, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
     let mut followers: Vec<FollowerRecord> = Vec::new();
@@ -525,9 +963,99 @@ pub fn get_followers_from_db(filepath: &String, pcid: u64) -> Result<Vec<Followe
     //Err(Box::new(HydraError(format!("Failed to get followers for: [{}].", pcid).into())))
 }
 
-
-//GetSet a reply in the database
-pub fn add_reply_to_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Box<dyn Error>> {
+pub fn get_follower_count_from_db(filepath: &String, pcid: u64) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    COUNT(*) \
+                                 FROM followers \
+                                 WHERE pcid = :pcid \
+                                   AND status = 'active'")?;
+    let count = stmt.query_row(&[(":pcid", pcid.to_string().as_str())], |row| {
+        row.get(0)
+    })?;
+
+    return Ok(count);
+}
+
+pub fn get_total_follower_count_from_db(filepath: &String) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    COUNT(*) \
+                                 FROM followers \
+                                 WHERE status = 'active'")?;
+    let count = stmt.query_row([], |row| {
+        row.get(0)
+    })?;
+
+    return Ok(count);
+}
+
+pub fn get_followed_actor_count_from_db(filepath: &String) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    COUNT(DISTINCT pcid) \
+                                 FROM followers \
+                                 WHERE status = 'active'")?;
+    let count = stmt.query_row([], |row| {
+        row.get(0)
+    })?;
+
+    return Ok(count);
+}
+
+pub fn get_followers_page_from_db(filepath: &String, pcid: u64, offset: u64, max: u64) -> Result<Vec<FollowerRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut followers: Vec<FollowerRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    pcid, \
+                                    actor,\
+                                    instance, \
+                                    inbox, \
+                                    shared_inbox, \
+                                    status \
+                                 FROM followers \
+                                 WHERE pcid = :pcid \
+                                   AND status = 'active' \
+                                 ORDER BY rowid DESC \
+                                 LIMIT :max \
+                                 OFFSET :offset")?;
+    let rows = stmt.query_map(
+        &[
+            (":max", max.to_string().as_str()),
+            (":offset", offset.to_string().as_str()),
+            (":pcid", pcid.to_string().as_str())
+        ],
+        |row| {
+            Ok(FollowerRecord {
+                pcid: row.get(0)?,
+                actor: row.get(1)?,
+                instance: row.get(2)?,
+                inbox: row.get(3)?,
+                shared_inbox: row.get(4)?,
+                status: row.get(5)?,
+            })
+        })?;
+
+    //Parse the results
+    for row in rows {
+        let follower: FollowerRecord = row?;
+        followers.push(follower);
+    }
+
+    return Ok(followers);
+}
+
+
+//GetSet a reply in the database
+pub fn add_reply_to_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
     match conn.execute("INSERT INTO replies (\
@@ -540,9 +1068,10 @@ pub fn add_reply_to_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Bo
                                          sensitive, \
                                          published, \
                                          received,\
-                                         conversation \
+                                         conversation, \
+                                         edited \
                                     ) \
-                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                        params![
                            reply.pcid,
                            reply.statusid,
@@ -553,7 +1082,8 @@ pub fn add_reply_to_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Bo
                            reply.sensitive,
                            reply.published,
                            reply.received,
-                           reply.conversation
+                           reply.conversation,
+                           reply.edited
                        ],
     ) {
         Ok(_) => {
@@ -565,13 +1095,42 @@ pub fn add_reply_to_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Bo
         }
     }
 }
-//TODO
+//Only the actor the reply is attributed to can edit it
+pub fn update_reply_in_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("UPDATE replies \
+                        SET content = ?1, \
+                            sensitive = ?2, \
+                            edited = ?3 \
+                        WHERE objectid = ?4 \
+                          AND attributedto = ?5",
+                       params![
+                           reply.content,
+                           reply.sensitive,
+                           reply.edited,
+                           reply.objectid,
+                           reply.attributedto,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to update reply: [{}].", reply.objectid).into())));
+        }
+    }
+}
+
+//Only the actor the reply is attributed to can remove it
 pub fn remove_reply_from_db(filepath: &String, reply: ReplyRecord) -> Result<bool, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    match conn.execute("DELETE FROM replies WHERE objectid=?",
+    match conn.execute("DELETE FROM replies WHERE objectid=?1 AND attributedto=?2",
                        params![
                            reply.objectid,
+                           reply.attributedto,
                        ],
     ) {
         Ok(_) => {
@@ -584,6 +1143,24 @@ pub fn remove_reply_from_db(filepath: &String, reply: ReplyRecord) -> Result<boo
     }
 }
 
+pub fn remove_replies_from_db_by_actor(filepath: &String, attributedto: String) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("DELETE FROM replies WHERE attributedto=?1",
+                       params![
+                           attributedto,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove replies by: [{}].", attributedto).into())));
+        }
+    }
+}
+
 pub fn get_replies_from_db_by_episode(filepath: &String, pcid: u64, statusid: String) -> Result<Vec<ReplyRecord>, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
     let mut replies: Vec<ReplyRecord> = Vec::new();
@@ -599,7 +1176,9 @@ pub fn get_replies_from_db_by_episode(filepath: &String, pcid: u64, statusid: St
                                      content, \
                                      sensitive, \
                                      published, \
-                                     received \
+                                     received, \
+                                     conversation, \
+                                     edited \
                                  FROM replies \
                                  WHERE pcid = :pcid \
                                    AND statusid = :statusid \
@@ -623,6 +1202,7 @@ pub fn get_replies_from_db_by_episode(filepath: &String, pcid: u64, statusid: St
                 published: row.get(7)?,
                 received: row.get(8)?,
                 conversation: row.get(9)?,
+                edited: row.get(10)?,
             })
         }).unwrap();
 
@@ -638,6 +1218,143 @@ pub fn get_replies_from_db_by_episode(filepath: &String, pcid: u64, statusid: St
     //Err(Box::new(HydraError(format!("Failed to get followers for: [{}].", pcid).into())))
 }
 
+pub fn get_reply_count_from_db_by_episode(filepath: &String, pcid: u64, statusid: String) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                     COUNT(*) \
+                                 FROM replies \
+                                 WHERE pcid = :pcid \
+                                   AND statusid = :statusid")?;
+    let count = stmt.query_row(
+        &[
+            (":pcid", pcid.to_string().as_str()),
+            (":statusid", statusid.as_str())
+        ],
+        |row| {
+            row.get(0)
+        })?;
+
+    return Ok(count);
+}
+
+pub fn get_replies_page_from_db_by_episode(filepath: &String, pcid: u64, statusid: String, offset: u64, max: u64) -> Result<Vec<ReplyRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut replies: Vec<ReplyRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                     pcid, \
+                                     statusid, \
+                                     objectid, \
+                                     objecttype, \
+                                     attributedto, \
+                                     content, \
+                                     sensitive, \
+                                     published, \
+                                     received, \
+                                     conversation, \
+                                     edited \
+                                 FROM replies \
+                                 WHERE pcid = :pcid \
+                                   AND statusid = :statusid \
+                                 ORDER BY received ASC \
+                                 LIMIT :max \
+                                 OFFSET :offset")?;
+    let rows = stmt.query_map(
+        &[
+            (":max", max.to_string().as_str()),
+            (":offset", offset.to_string().as_str()),
+            (":pcid", pcid.to_string().as_str()),
+            (":statusid", statusid.as_str())
+        ],
+        |row| {
+            Ok(ReplyRecord {
+                pcid: row.get(0)?,
+                statusid: row.get(1)?,
+                objectid: row.get(2)?,
+                objecttype: row.get(3)?,
+                attributedto: row.get(4)?,
+                content: row.get(5)?,
+                sensitive: row.get(6)?,
+                published: row.get(7)?,
+                received: row.get(8)?,
+                conversation: row.get(9)?,
+                edited: row.get(10)?,
+            })
+        })?;
+
+    //Parse the results
+    for row in rows {
+        let reply: ReplyRecord = row?;
+        replies.push(reply);
+    }
+
+    return Ok(replies);
+}
+
+pub fn get_replies_from_db_by_context(filepath: &String, pcid: u64, statusid: String, conversations: Vec<String>) -> Result<Vec<ReplyRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut replies: Vec<ReplyRecord> = Vec::new();
+    let max = 1000; //TODO: debug - hard limit for now
+
+    //Build the parameter list, the conversation values follow the pcid, statusid and max
+    let mut query_params = vec![pcid.to_string(), statusid.clone(), max.to_string()];
+    let mut conversation_placeholders = vec![];
+    for conversation in conversations {
+        if conversation.is_empty() {
+            continue;
+        }
+        query_params.push(conversation);
+        conversation_placeholders.push(format!("?{}", query_params.len()));
+    }
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare(format!("SELECT \
+                                     pcid, \
+                                     statusid, \
+                                     objectid, \
+                                     objecttype, \
+                                     attributedto, \
+                                     content, \
+                                     sensitive, \
+                                     published, \
+                                     received, \
+                                     conversation, \
+                                     edited \
+                                 FROM replies \
+                                 WHERE (pcid = ?1 AND statusid = ?2) \
+                                    OR conversation IN ({}) \
+                                 ORDER BY received ASC \
+                                 LIMIT ?3", conversation_placeholders.join(", ")).as_str())?;
+    let rows = stmt.query_map(
+        params_from_iter(query_params.iter()),
+        |row| {
+            Ok(ReplyRecord {
+                pcid: row.get(0)?,
+                statusid: row.get(1)?,
+                objectid: row.get(2)?,
+                objecttype: row.get(3)?,
+                attributedto: row.get(4)?,
+                content: row.get(5)?,
+                sensitive: row.get(6)?,
+                published: row.get(7)?,
+                received: row.get(8)?,
+                conversation: row.get(9)?,
+                edited: row.get(10)?,
+            })
+        })?;
+
+    //Parse the results
+    for row in rows {
+        let reply: ReplyRecord = row?;
+        replies.push(reply);
+    }
+
+    return Ok(replies);
+}
+
 pub fn get_a_reply_by_conversation(filepath: &String, conversation: String) -> Result<Vec<ReplyRecord>, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
     let mut replies: Vec<ReplyRecord> = Vec::new();
@@ -653,7 +1370,8 @@ pub fn get_a_reply_by_conversation(filepath: &String, conversation: String) -> R
                                      sensitive, \
                                      published, \
                                      received,\
-                                     conversation \
+                                     conversation, \
+                                     edited \
                                  FROM replies \
                                  WHERE conversation = :conversation \
                                  ORDER BY received DESC")?;
@@ -673,6 +1391,7 @@ pub fn get_a_reply_by_conversation(filepath: &String, conversation: String) -> R
                 published: row.get(7)?,
                 received: row.get(8)?,
                 conversation: row.get(9)?,
+                edited: row.get(10)?,
             })
         }).unwrap();
 
@@ -686,4 +1405,588 @@ pub fn get_a_reply_by_conversation(filepath: &String, conversation: String) -> R
 
 
     //Err(Box::new(HydraError(format!("Failed to get followers for: [{}].", pcid).into())))
-}
\ No newline at end of file
+}
+
+//GetSet likes and shares of episodes in the database
+fn add_reaction_to_db(filepath: &String, table: &str, reaction: ReactionRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute(format!("INSERT OR REPLACE INTO {} (\
+                                      pcid, \
+                                      statusid, \
+                                      actor, \
+                                      activityid, \
+                                      received \
+                                    ) \
+                        VALUES (?1, ?2, ?3, ?4, ?5)", table).as_str(),
+                       params![
+                           reaction.pcid,
+                           reaction.statusid,
+                           reaction.actor,
+                           reaction.activityid,
+                           reaction.received
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add {} entry: [{}].", table, reaction.activityid).into())));
+        }
+    }
+}
+
+fn remove_reaction_from_db(filepath: &String, table: &str, reaction: ReactionRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute(format!("DELETE FROM {} WHERE activityid=?1 AND actor=?2", table).as_str(),
+                       params![
+                           reaction.activityid,
+                           reaction.actor,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove {} entry: [{}].", table, reaction.activityid).into())));
+        }
+    }
+}
+
+fn remove_reactions_from_db_by_actor(filepath: &String, table: &str, actor: String) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute(format!("DELETE FROM {} WHERE actor=?1", table).as_str(),
+                       params![
+                           actor,
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove {} entries by: [{}].", table, actor).into())));
+        }
+    }
+}
+
+fn get_reaction_count_from_db_by_episode(filepath: &String, table: &str, pcid: u64, statusid: String) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare(format!("SELECT COUNT(*) \
+                                 FROM {} \
+                                 WHERE pcid = :pcid \
+                                   AND statusid = :statusid", table).as_str())?;
+    let count: u64 = stmt.query_row(
+        &[
+            (":pcid", pcid.to_string().as_str()),
+            (":statusid", statusid.as_str())
+        ],
+        |row| row.get(0),
+    )?;
+
+    return Ok(count);
+}
+
+pub fn add_like_to_db(filepath: &String, like: ReactionRecord) -> Result<bool, Box<dyn Error>> {
+    return add_reaction_to_db(filepath, "likes", like);
+}
+
+pub fn remove_like_from_db(filepath: &String, like: ReactionRecord) -> Result<bool, Box<dyn Error>> {
+    return remove_reaction_from_db(filepath, "likes", like);
+}
+
+pub fn remove_likes_from_db_by_actor(filepath: &String, actor: String) -> Result<bool, Box<dyn Error>> {
+    return remove_reactions_from_db_by_actor(filepath, "likes", actor);
+}
+
+pub fn get_like_count_from_db_by_episode(filepath: &String, pcid: u64, statusid: String) -> Result<u64, Box<dyn Error>> {
+    return get_reaction_count_from_db_by_episode(filepath, "likes", pcid, statusid);
+}
+
+pub fn add_share_to_db(filepath: &String, share: ReactionRecord) -> Result<bool, Box<dyn Error>> {
+    return add_reaction_to_db(filepath, "shares", share);
+}
+
+pub fn remove_share_from_db(filepath: &String, share: ReactionRecord) -> Result<bool, Box<dyn Error>> {
+    return remove_reaction_from_db(filepath, "shares", share);
+}
+
+pub fn remove_shares_from_db_by_actor(filepath: &String, actor: String) -> Result<bool, Box<dyn Error>> {
+    return remove_reactions_from_db_by_actor(filepath, "shares", actor);
+}
+
+pub fn get_share_count_from_db_by_episode(filepath: &String, pcid: u64, statusid: String) -> Result<u64, Box<dyn Error>> {
+    return get_reaction_count_from_db_by_episode(filepath, "shares", pcid, statusid);
+}
+
+//GetSet a remote actor's public key in the database
+pub fn add_remote_key_to_db(filepath: &String, remote_key: RemoteKeyRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("INSERT OR REPLACE INTO remote_keys (\
+                                      keyid, \
+                                      owner, \
+                                      pem_public_key, \
+                                      fetched \
+                                    ) \
+                        VALUES (?1, ?2, ?3, ?4)",
+                       params![
+                           remote_key.keyid,
+                           remote_key.owner,
+                           remote_key.pem_public_key,
+                           remote_key.fetched
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add remote key: [{}].", remote_key.keyid).into())));
+        }
+    }
+}
+
+//Swap out every key held for the key's owner, for when the owner rotates their key
+pub fn replace_remote_key_in_db(filepath: &String, remote_key: RemoteKeyRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    if let Err(e) = conn.execute("DELETE FROM remote_keys WHERE owner=?1",
+                                 params![
+                                     remote_key.owner,
+                                 ],
+    ) {
+        eprintln!("{}", e);
+        return Err(Box::new(HydraError(format!("Failed to remove remote keys of: [{}].", remote_key.owner).into())));
+    }
+
+    return add_remote_key_to_db(filepath, remote_key);
+}
+
+pub fn get_remote_key_from_db(filepath: &String, keyid: String) -> Result<RemoteKeyRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut remote_keys: Vec<RemoteKeyRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    keyid, \
+                                    owner, \
+                                    pem_public_key, \
+                                    fetched \
+                                 FROM remote_keys \
+                                 WHERE keyid = :keyid \
+                                 LIMIT 1")?;
+    let rows = stmt.query_map(&[(":keyid", keyid.as_str())], |row| {
+        Ok(RemoteKeyRecord {
+            keyid: row.get(0)?,
+            owner: row.get(1)?,
+            pem_public_key: row.get(2)?,
+            fetched: row.get(3)?,
+        })
+    })?;
+
+    //Parse the results
+    for row in rows {
+        let remote_key: RemoteKeyRecord = row?;
+        remote_keys.push(remote_key);
+    }
+
+    if remote_keys.len() > 0 {
+        return Ok(remote_keys[0].clone());
+    }
+
+    Err(Box::new(HydraError(format!("Failed to get remote key: [{}].", keyid).into())))
+}
+
+
+//GetSet a note authored by one of our actors in the database
+pub fn add_note_to_db(filepath: &String, note: NoteRecord) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("INSERT INTO notes (\
+                                      pcid, \
+                                      content, \
+                                      in_reply_to, \
+                                      published \
+                                    ) \
+                        VALUES (?1, ?2, ?3, ?4)",
+                       params![
+                           note.pcid,
+                           note.content,
+                           note.in_reply_to,
+                           note.published
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(conn.last_insert_rowid() as u64)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add note for: [{}].", note.pcid).into())));
+        }
+    }
+}
+
+pub fn get_note_from_db(filepath: &String, pcid: u64, id: u64) -> Result<NoteRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut notes: Vec<NoteRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    id, \
+                                    pcid, \
+                                    content, \
+                                    in_reply_to, \
+                                    published \
+                                 FROM notes \
+                                 WHERE id = ?1 \
+                                   AND pcid = ?2 \
+                                 LIMIT 1")?;
+    let rows = stmt.query_map(params![id, pcid], |row| {
+        Ok(NoteRecord {
+            id: row.get(0)?,
+            pcid: row.get(1)?,
+            content: row.get(2)?,
+            in_reply_to: row.get(3)?,
+            published: row.get(4)?,
+        })
+    })?;
+
+    //Parse the results
+    for row in rows {
+        let note: NoteRecord = row?;
+        notes.push(note);
+    }
+
+    if notes.len() > 0 {
+        return Ok(notes[0].clone());
+    }
+
+    Err(Box::new(HydraError(format!("Failed to get note: [{}|{}].", pcid, id).into())))
+}
+
+
+//GetSet an outbound delivery in the database
+pub fn add_delivery_to_db(filepath: &String, delivery: DeliveryRecord) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("INSERT INTO deliveries (\
+                                      pcid, \
+                                      inbox, \
+                                      activity, \
+                                      attempts, \
+                                      next_attempt, \
+                                      created, \
+                                      status, \
+                                      last_error \
+                                    ) \
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                       params![
+                           delivery.pcid,
+                           delivery.inbox,
+                           delivery.activity,
+                           delivery.attempts,
+                           delivery.next_attempt,
+                           delivery.created,
+                           delivery.status,
+                           delivery.last_error
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(conn.last_insert_rowid() as u64)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add delivery for: [{}].", delivery.inbox).into())));
+        }
+    }
+}
+
+pub fn update_delivery_in_db(filepath: &String, delivery: DeliveryRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("UPDATE deliveries \
+                        SET attempts = ?1, \
+                            next_attempt = ?2, \
+                            status = ?3, \
+                            last_error = ?4 \
+                        WHERE id = ?5",
+                       params![
+                           delivery.attempts,
+                           delivery.next_attempt,
+                           delivery.status,
+                           delivery.last_error,
+                           delivery.id
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to update delivery: [{}].", delivery.id).into())));
+        }
+    }
+}
+
+//Pending deliveries whose next attempt is due, oldest first
+pub fn get_due_deliveries_from_db(filepath: &String, now: u64, max: u64) -> Result<Vec<DeliveryRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut deliveries: Vec<DeliveryRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    id, \
+                                    pcid, \
+                                    inbox, \
+                                    activity, \
+                                    attempts, \
+                                    next_attempt, \
+                                    created, \
+                                    status, \
+                                    last_error \
+                                 FROM deliveries \
+                                 WHERE status = 'pending' \
+                                   AND next_attempt <= :now \
+                                 ORDER BY next_attempt ASC \
+                                 LIMIT :max")?;
+    let rows = stmt.query_map(
+        &[
+            (":now", now.to_string().as_str()),
+            (":max", max.to_string().as_str()),
+        ],
+        |row| {
+            Ok(DeliveryRecord {
+                id: row.get(0)?,
+                pcid: row.get(1)?,
+                inbox: row.get(2)?,
+                activity: row.get(3)?,
+                attempts: row.get(4)?,
+                next_attempt: row.get(5)?,
+                created: row.get(6)?,
+                status: row.get(7)?,
+                last_error: row.get(8)?,
+            })
+        })?;
+
+    //Parse the results
+    for row in rows {
+        let delivery: DeliveryRecord = row?;
+        deliveries.push(delivery);
+    }
+
+    return Ok(deliveries);
+}
+
+
+//GetSet an episode the tracker has already dealt with in the database
+pub fn add_seen_episode_to_db(filepath: &String, seen: SeenEpisodeRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("INSERT OR REPLACE INTO seen_episodes (\
+                                      pcid, \
+                                      guid, \
+                                      published \
+                                    ) \
+                        VALUES (?1, ?2, ?3)",
+                       params![
+                           seen.pcid,
+                           seen.guid,
+                           seen.published
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add seen episode: [{}] for pcid: [{}].", seen.guid, seen.pcid).into())));
+        }
+    }
+}
+
+pub fn get_seen_episode_guids_from_db(filepath: &String, pcid: u64) -> Result<Vec<String>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut guids: Vec<String> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    guid \
+                                 FROM seen_episodes \
+                                 WHERE pcid = ?1")?;
+    let rows = stmt.query_map(params![pcid], |row| row.get(0))?;
+
+    //Parse the results
+    for row in rows {
+        let guid: String = row?;
+        guids.push(guid);
+    }
+
+    return Ok(guids);
+}
+
+//Episodes published before the high water mark can never be announced again, so they don't need remembering
+pub fn remove_seen_episodes_from_db_before(filepath: &String, pcid: u64, published: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("DELETE FROM seen_episodes WHERE pcid = ?1 AND published < ?2", params![pcid, published]) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove seen episodes for pcid: [{}].", pcid).into())));
+        }
+    }
+}
+
+
+//GetSet a named piece of running state in the database
+pub fn update_state_value_in_db(filepath: &String, name: String, value: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("INSERT OR REPLACE INTO app_state (name, value) VALUES (?1, ?2)", params![name, value]) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to update state: [{}].", name).into())));
+        }
+    }
+}
+
+pub fn get_state_value_from_db(filepath: &String, name: String) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare("SELECT value FROM app_state WHERE name = ?1")?;
+    match stmt.query_row(params![name], |row| row.get(0)) {
+        Ok(value) => {
+            return Ok(value);
+        }
+        Err(e) => {
+            return Err(Box::new(HydraError(format!("No state value for: [{}].  Error: {:#?}", name, e).into())));
+        }
+    }
+}
+
+
+//GetSet a cached podcast index api response in the database
+pub fn add_api_cache_entry_to_db(filepath: &String, entry: ApiCacheRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("INSERT OR REPLACE INTO api_cache (\
+                                      key, \
+                                      body, \
+                                      not_found, \
+                                      expires \
+                                    ) \
+                        VALUES (?1, ?2, ?3, ?4)",
+                       params![
+                           entry.key,
+                           entry.body,
+                           entry.not_found,
+                           entry.expires
+                       ],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to add api cache entry: [{}].", entry.key).into())));
+        }
+    }
+}
+
+pub fn get_api_cache_entry_from_db(filepath: &String, key: String, now: u64) -> Result<ApiCacheRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut entries: Vec<ApiCacheRecord> = Vec::new();
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare("SELECT \
+                                    key, \
+                                    body, \
+                                    not_found, \
+                                    expires \
+                                 FROM api_cache \
+                                 WHERE key = ?1 \
+                                   AND expires > ?2 \
+                                 LIMIT 1")?;
+    let rows = stmt.query_map(params![key, now], |row| {
+        Ok(ApiCacheRecord {
+            key: row.get(0)?,
+            body: row.get(1)?,
+            not_found: row.get(2)?,
+            expires: row.get(3)?,
+        })
+    })?;
+
+    //Parse the results
+    for row in rows {
+        let entry: ApiCacheRecord = row?;
+        entries.push(entry);
+    }
+
+    if entries.len() > 0 {
+        return Ok(entries[0].clone());
+    }
+
+    Err(Box::new(HydraError(format!("No api cache entry for: [{}].", key).into())))
+}
+
+pub fn remove_api_cache_entries_from_db_by_prefix(filepath: &String, prefix: String) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("DELETE FROM api_cache WHERE substr(key, 1, length(?1)) = ?1", params![prefix]) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove api cache entries: [{}].", prefix).into())));
+        }
+    }
+}
+
+//Keep only the max_entries entries that expire last
+pub fn remove_api_cache_entries_from_db_beyond(filepath: &String, max_entries: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("DELETE FROM api_cache \
+                        WHERE rowid NOT IN (\
+                            SELECT rowid \
+                            FROM api_cache \
+                            ORDER BY expires DESC, rowid DESC \
+                            LIMIT ?1\
+                        )",
+                       params![max_entries],
+    ) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to trim api cache entries.").into())));
+        }
+    }
+}
+
+pub fn remove_expired_api_cache_entries_from_db(filepath: &String, now: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    match conn.execute("DELETE FROM api_cache WHERE expires <= ?1", params![now]) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to remove expired api cache entries.").into())));
+        }
+    }
+}
@@ -1,21 +1,31 @@
-use crate::{Context, crypto_rsa, http_signature, Response, USER_AGENT_PARAM};
+use crate::{Context, crypto_rsa, http_signature, Response};
 use hyper::StatusCode;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::marker::PhantomData;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use serde::{Deserialize, Serialize, Deserializer};
 use serde::de::{self, MapAccess, Visitor};
-use std::time::{SystemTime, UNIX_EPOCH};
-use sha1::{Sha1};
-use sha2::{Digest};
-use urlencoding;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use reqwest::header;
 use chrono::{TimeZone, Utc};
 use rsa::{RsaPrivateKey, RsaPublicKey};
 //use rsa::pkcs1v15::{SigningKey, VerifyingKey};
 //use rsa::signature::{Keypair, RandomizedSigner, SignatureEncoding, Verifier, Signer};
-use dbif::{ActorRecord, FollowerRecord, ReplyRecord};
+use crate::podcastindex::{
+    PodcastIndexClient,
+    PIError,
+    PIResult,
+    PIEpisode,
+    PIEpisodes,
+    PIFeed,
+    PIItem,
+    PILiveItem,
+    PIPodcast,
+};
+use dbif::{ActorRecord, DeliveryRecord, FollowerRecord, NoteRecord, ReactionRecord, RemoteKeyRecord, ReplyRecord};
 use base64::{Engine as _, engine::{general_purpose}};
 //use rand::rngs::ThreadRng;
 //use sha256::digest;
@@ -24,7 +34,32 @@ use void::Void;
 
 
 //Globals ----------------------------------------------------------------------------------------------------
+#[cfg(not(test))]
 const AP_DATABASE_FILE: &str = "database.db";
+#[cfg(test)]
+const AP_DATABASE_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/handler-test.db");
+const AP_DEFAULT_BASE_URL: &str = "https://ap.podcastindex.org";
+static AP_BASE_URL: OnceLock<String> = OnceLock::new();
+static AP_HIDE_FOLLOWERS: AtomicBool = AtomicBool::new(false);
+static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+const AP_HTTP_TIMEOUT_SECONDS: u64 = 30;
+const AP_COLLECTION_PAGE_SIZE: u64 = 40;
+const AP_OUTBOX_PAGE_SIZE: usize = 20;
+const AP_OUTBOX_MAX_EPISODES: u64 = 1000;
+const AP_OUTBOX_MAX_RECENT_EPISODES: u64 = 100;
+const AP_PROFILE_EPISODE_COUNT: u64 = 10;
+const AP_NODEINFO_MONTH_SECONDS: u64 = 30 * 86400;
+const AP_NODEINFO_HALFYEAR_SECONDS: u64 = 180 * 86400;
+const AP_DELIVERY_BATCH_SIZE: u64 = 50;
+const AP_DELIVERY_CONCURRENCY: usize = 10;
+const AP_DELIVERY_RETRY_BASE_SECONDS: u64 = 60;
+const AP_DELIVERY_RETRY_MAX_SECONDS: u64 = 21600;
+const AP_DEFAULT_DELIVERY_HORIZON_HOURS: u64 = 72;
+static AP_DELIVERY_HORIZON: AtomicU64 = AtomicU64::new(AP_DEFAULT_DELIVERY_HORIZON_HOURS * 3600);
+pub const AP_TRACKER_EPISODE_FETCH: u64 = 20;
+const AP_DEFAULT_TRACKER_MAX_NEW_EPISODES: u64 = 5;
+static AP_TRACKER_MAX_NEW_EPISODES: AtomicU64 = AtomicU64::new(AP_DEFAULT_TRACKER_MAX_NEW_EPISODES);
+static AP_EPISODE_BACKLOG_POLICY: OnceLock<EpisodeBacklogPolicy> = OnceLock::new();
 
 //Structs ----------------------------------------------------------------------------------------------------
 #[allow(non_snake_case)]
@@ -42,10 +77,61 @@ pub struct Link {
 #[derive(Serialize, Deserialize)]
 pub struct Webfinger {
     subject: String,
+    #[serde(default)]
     aliases: Vec<String>,
     links: Vec<Link>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfoLinks {
+    links: Vec<Link>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfoSoftware {
+    name: String,
+    version: String,
+    homepage: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfoServices {
+    inbound: Vec<String>,
+    outbound: Vec<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfoUsers {
+    total: u64,
+    activeHalfyear: u64,
+    activeMonth: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfoUsage {
+    users: NodeInfoUsers,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfoMetadata {
+    nodeName: String,
+    followers: u64,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct NodeInfo {
+    version: String,
+    software: NodeInfoSoftware,
+    protocols: Vec<String>,
+    services: NodeInfoServices,
+    openRegistrations: bool,
+    usage: NodeInfoUsage,
+    metadata: NodeInfoMetadata,
+}
+
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PublicKey {
@@ -198,18 +284,27 @@ pub struct InboxRequestObject {
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ReplyCollection {
+    #[serde(rename = "@context", skip_deserializing, skip_serializing_if = "Option::is_none")]
+    at_context: Option<String>,
     id: Option<String>,
     r#type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    totalItems: Option<u64>,
     first: Option<ReplyCollectionPage>,
 }
 
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ReplyCollectionPage {
+    #[serde(rename = "@context", skip_deserializing, skip_serializing_if = "Option::is_none")]
+    at_context: Option<String>,
     id: Option<String>,
     r#type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     next: Option<String>,
     partOf: Option<String>,
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    items: Option<Vec<String>>,
 }
 
 #[allow(non_snake_case)]
@@ -230,7 +325,8 @@ pub struct OutboxConfig {
     context: String,
     id: String,
     r#type: String,
-    totalItems: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    totalItems: Option<u64>,
     first: String,
     last: String,
 }
@@ -279,6 +375,38 @@ pub struct Item {
     object: Object,
 }
 
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct OrderedCollection {
+    #[serde(rename = "@context", skip_serializing_if = "Option::is_none")]
+    at_context: Option<String>,
+    id: String,
+    r#type: String,
+    totalItems: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    orderedItems: Option<Vec<String>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct OrderedCollectionPage<T> {
+    #[serde(rename = "@context")]
+    at_context: String,
+    id: String,
+    r#type: String,
+    totalItems: u64,
+    partOf: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev: Option<String>,
+    orderedItems: Vec<T>,
+}
+
 #[allow(non_snake_case)]
 #[derive(Serialize, Deserialize)]
 pub struct OutboxPaged {
@@ -286,10 +414,13 @@ pub struct OutboxPaged {
     context: String,
     id: String,
     r#type: String,
-    next: String,
-    prev: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev: Option<String>,
     partOf: String,
-    totalItems: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    totalItems: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     orderedItems: Option<Vec<Item>>,
 }
@@ -349,140 +480,33 @@ pub struct Status {
     attachment: Option<Vec<NoteAttachment>>,
     actor: String,
     tag: Vec<String>,
-    replies: Option<String>,    //TODO: This should refer to some sort of Collection pub struct
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PIFeed {
-    pub id: u64,
-    pub podcastGuid: String,
-    pub medium: String,
-    pub title: String,
-    pub url: String,
-    pub originalUrl: String,
-    pub link: String,
-    pub description: String,
-    pub author: String,
-    pub ownerName: String,
-    pub image: String,
-    pub artwork: String,
-    pub episodeCount: u64,
-    pub funding: Option<PIFunding>,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PIFunding {
-    url: Option<String>,
-    message: Option<String>,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PIPodcast {
-    pub status: String,
-    pub feed: PIFeed,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PIItem {
-    pub id: u64,
-    pub title: String,
-    pub link: String,
-    pub description: String,
-    pub guid: String,
-    pub datePublished: u64,
-    pub datePublishedPretty: String,
-    pub enclosureUrl: String,
-    pub enclosureType: String,
-    pub duration: u64,
-    pub image: String,
-    pub feedImage: String,
-    pub feedUrl: String,
-    pub podcastGuid: String,
-    pub feedId: u64,
-    pub feedItunesId: Option<u64>,
-    pub socialInteract: Option<Vec<PISocialInteract>>,
-    pub transcripts: Option<Vec<PITranscript>>,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PITranscript {
-    pub url: Option<String>,
-    pub r#type: Option<String>,
-}
-
-#[allow(non_snake_case)]
-#[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize)]
-pub enum PISocialInteractProtocol {
-    activitypub,
-    xmpp,
-    bluesky,
-    nostr,
-    twitter,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PISocialInteract {
-    pub uri: Option<String>,
-    pub protocol: Option<PISocialInteractProtocol>,
-    pub accountId: Option<String>,
-    pub accountUrl: Option<String>,
-    pub priority: Option<u64>,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PIEpisodes {
-    pub status: String,
-    pub items: Vec<PIItem>,
-    pub count: u64,
+    replies: Option<ReplyCollection>,
+    likes: Option<OrderedCollection>,
+    shares: Option<OrderedCollection>,
 }
 
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize)]
-pub struct PIEpisode {
-    pub status: String,
-    pub episode: PIItem,
+#[derive(Debug, PartialEq)]
+enum PodcastLookup {
+    FeedId(String),
+    Guid(String),
+    FeedUrl(String),
+    Slug(String),
 }
 
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct PILiveItems {
-    pub status: String,
-    pub liveItems: Vec<PILiveItem>,
-    pub count: u64,
-    pub query: String,
-    pub description: String,
+//##: What to do when more episodes show up at once than the tracker will announce, like a whole back catalogue
+//##: being imported.  Recent announces the newest ones up to the limit, Latest only the newest and Skip none
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EpisodeBacklogPolicy {
+    Recent,
+    Latest,
+    Skip,
 }
 
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct PILiveItem {
-    pub id: u64,
-    pub title: String,
-    pub link: String,
-    pub description: String,
-    pub guid: String,
-    pub datePublished: u64,
-    pub datePublishedPretty: String,
-    pub enclosureUrl: String,
-    pub enclosureType: String,
-    pub startTime: Option<u64>,
-    pub endTime: Option<u64>,
-    pub status: String,
-    pub contentLink: String,
-    pub image: String,
-    pub feedImage: String,
-    pub feedId: u64,
-    pub feedUrl: String,
-    pub podcastGuid: String,
-    pub feedItunesId: Option<u64>,
+//##: Which of a podcast's episodes the tracker should announce, oldest first, and which it has now dealt with
+pub struct NewEpisodes<'a> {
+    pub announce: Vec<&'a PIItem>,
+    pub seen: Vec<&'a PIItem>,
+    pub last_published: u64,
 }
 
 #[derive(Debug)]
@@ -606,7 +630,7 @@ pub async fn webfinger(ctx: Context) -> Response {
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -618,111 +642,298 @@ pub async fn webfinger(ctx: Context) -> Response {
     );
     //println!("Context: {:#?}", ctx);
 
-    //Make sure a session param was given
-    let guid;
+    //Make sure a resource was given and that it names one of our podcasts
+    let resource;
+    let lookup;
     match params.get("resource") {
-        Some(resource) => {
-            println!("  Id: {}\n", resource);
-            let parts = resource.replace("acct:", "");
-            guid = parts.split("@").next().unwrap().to_string();
+        Some(resource_param) => {
+            println!("  Id: {}\n", resource_param);
+            resource = resource_param.to_string();
+            match parse_webfinger_resource(&resource, ap_base_url(), &ap_host()) {
+                Some(podcast_lookup) => {
+                    lookup = podcast_lookup;
+                }
+                None => {
+                    println!("Unknown resource: [{}].\n", resource);
+                    return webfinger_not_found(&resource);
+                }
+            }
         }
         None => {
             println!("Invalid resource.\n");
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("No resource given.").into())
+                .body("No resource given.".to_string().into())
                 .unwrap();
         }
     }
 
-    let podcast_guid = guid.clone();
-
-    //Lookup API of podcast
-    let podcast_data: PIPodcast;
-    let api_response = api_get_podcast(
-        &ctx.pi_auth.key,
-        &ctx.pi_auth.secret,
-        &podcast_guid,
-    ).await;
-    match api_response {
-        Ok(response_body) => {
-            //eprintln!("{:#?}", response_body);
-            match serde_json::from_str(response_body.as_str()) {
-                Ok(data) => {
-                    podcast_data = data;
-                    println!("{}", podcast_data.feed.image);
+    //Lookup API of podcast by whatever the listener knows it by
+    let api_response;
+    match &lookup {
+        PodcastLookup::FeedId(feed_id) => {
+            api_response = ctx.pi_client.get_podcast(feed_id).await;
+        }
+        PodcastLookup::Guid(guid) => {
+            api_response = ctx.pi_client.get_podcast_by_guid(guid).await;
+        }
+        PodcastLookup::FeedUrl(feed_url) => {
+            api_response = ctx.pi_client.get_podcast_by_feedurl(feed_url).await;
+        }
+        PodcastLookup::Slug(slug) => {
+            let feed_id = match api_find_podcast_id_by_slug(ctx.pi_client.as_ref(), slug).await {
+                Ok(Some(slug_feed_id)) => {
+                    slug_feed_id
+                }
+                Ok(None) | Err(PIError::NotFound(_)) => {
+                    println!("No podcast matches slug: [{}].\n", slug);
+                    return webfinger_not_found(&resource);
                 }
                 Err(e) => {
                     println!("Response prep error: [{:#?}].\n", e);
                     return hyper::Response::builder()
                         .status(StatusCode::from_u16(501).unwrap())
-                        .body(format!("Response prep error.").into())
+                        .body("Response prep error.".to_string().into())
                         .unwrap();
                 }
-            }
+            };
+            api_response = ctx.pi_client.get_podcast(&feed_id.to_string()).await;
+        }
+    }
+    let podcast_data: PIPodcast;
+    match api_response {
+        Ok(data) => {
+            podcast_data = data;
+            println!("{}", podcast_data.feed.image);
+        }
+        Err(PIError::NotFound(e)) => {
+            println!("Podcast not found: [{}].\n", e);
+            return webfinger_not_found(&resource);
         }
         Err(e) => {
             println!("Response prep error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(501).unwrap())
-                .body(format!("Response prep error.").into())
+                .body("Response prep error.".to_string().into())
                 .unwrap();
         }
     }
 
+    //##: Always answer with the canonical feed id account, whatever we were asked for
+    let podcast_guid = podcast_data.feed.id.to_string();
+
     //Construct a response
     let webfinger_data = Webfinger {
-        subject: format!("acct:{}@ap.podcastindex.org", podcast_guid).to_string(),
+        subject: format!("acct:{}@{}", podcast_guid, ap_host()).to_string(),
         aliases: vec!(
+            format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
             format!("https://podcastindex.org/podcast/{}", podcast_guid).to_string()
         ),
         links: vec!(
             Link {
                 rel: "http://webfinger.net/rel/profile-page".to_string(),
                 r#type: Some("text/html".to_string()),
-                href: Some(format!("https://ap.podcastindex.org/profiles?id={}", podcast_guid).to_string()),
+                href: Some(format!("{}/profiles?id={}", ap_base_url(), podcast_guid).to_string()),
                 template: None,
             },
             Link {
                 rel: "self".to_string(),
                 r#type: Some("application/activity+json".to_string()),
-                href: Some(format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string()),
+                href: Some(format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string()),
                 template: None,
             },
             Link {
                 rel: "http://webfinger.net/rel/avatar".to_string(),
                 r#type: Some("image/png".to_string()),
-                href: Some(format!("{}", podcast_data.feed.image).to_string()),
+                href: Some(podcast_data.feed.image.to_string().to_string()),
                 template: None,
             },
             Link {
                 rel: "http://ostatus.org/schema/1.0/subscribe".to_string(),
                 r#type: None,
                 href: None,
-                template: Some("https://ap.podcastindex.org/ostatus_subscribe?acct={uri}".to_string()),
+                template: Some(format!("{}/ostatus_subscribe?acct={{uri}}", ap_base_url()).to_string()),
             },
         ),
     };
 
-    let webfinger_json;
-    match serde_json::to_string_pretty(&webfinger_data) {
+    let webfinger_json = match serde_json::to_string_pretty(&webfinger_data) {
         Ok(json_result) => {
-            webfinger_json = json_result;
+            json_result
         }
         Err(e) => {
             println!("Response prep error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(500).unwrap())
-                .body(format!("Response prep error.").into())
+                .body("Response prep error.".to_string().into())
                 .unwrap();
         }
-    }
+    };
+
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/json; charset=utf-8")
+        .body(webfinger_json.to_string().into())
+        .unwrap()
+}
 
-    return hyper::Response::builder()
+fn webfinger_not_found(resource: &str) -> Response {
+    let error_json = serde_json::json!({
+        "subject": resource,
+        "error": "Unknown podcast.",
+    });
+
+    hyper::Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .header("Content-type", "application/jrd+json; charset=utf-8")
+        .body(format!("{}", error_json).into())
+        .unwrap()
+}
+
+pub async fn nodeinfo_links(ctx: Context) -> Response {
+
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+
+    //Point crawlers at the NodeInfo document we serve
+    let links_data = NodeInfoLinks {
+        links: vec!(
+            Link {
+                rel: "http://nodeinfo.diaspora.software/ns/schema/2.1".to_string(),
+                r#type: None,
+                href: Some(format!("{}/nodeinfo/2.1", ap_base_url()).to_string()),
+                template: None,
+            },
+        ),
+    };
+
+    let links_json = match serde_json::to_string_pretty(&links_data) {
+        Ok(json_result) => {
+            json_result
+        }
+        Err(e) => {
+            println!("Response prep error: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Response prep error.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/json; charset=utf-8")
-        .body(format!("{}", webfinger_json).into())
-        .unwrap();
+        .body(links_json.to_string().into())
+        .unwrap()
+}
+
+pub async fn nodeinfo(ctx: Context) -> Response {
+
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+
+    //##: Every podcast somebody has looked up gets an actor, but only the followed ones count as users.  They're
+    //##: active when they've sent an episode or note out to their followers within the window
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs();
+    let followed_actor_count = dbif::get_followed_actor_count_from_db(&AP_DATABASE_FILE.to_string()).unwrap_or(0);
+    let active_halfyear_count = dbif::get_active_actor_count_from_db(
+        &AP_DATABASE_FILE.to_string(),
+        now.saturating_sub(AP_NODEINFO_HALFYEAR_SECONDS),
+    ).unwrap_or(0);
+    let active_month_count = dbif::get_active_actor_count_from_db(
+        &AP_DATABASE_FILE.to_string(),
+        now.saturating_sub(AP_NODEINFO_MONTH_SECONDS),
+    ).unwrap_or(0);
+    let follower_count = dbif::get_total_follower_count_from_db(&AP_DATABASE_FILE.to_string()).unwrap_or(0);
+
+    let nodeinfo_data = NodeInfo {
+        version: "2.1".to_string(),
+        software: NodeInfoSoftware {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            homepage: "https://podcastindex.org".to_string(),
+        },
+        protocols: vec!(
+            "activitypub".to_string()
+        ),
+        services: NodeInfoServices {
+            inbound: vec!(),
+            outbound: vec!(),
+        },
+        openRegistrations: false,
+        usage: NodeInfoUsage {
+            users: NodeInfoUsers {
+                total: followed_actor_count,
+                activeHalfyear: active_halfyear_count,
+                activeMonth: active_month_count,
+            },
+        },
+        metadata: NodeInfoMetadata {
+            nodeName: ap_host(),
+            followers: follower_count,
+        },
+    };
+
+    let nodeinfo_json = match serde_json::to_string_pretty(&nodeinfo_data) {
+        Ok(json_result) => {
+            json_result
+        }
+        Err(e) => {
+            println!("Response prep error: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Response prep error.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            "Content-type",
+            "application/json; profile=\"http://nodeinfo.diaspora.software/ns/schema/2.1#\"; charset=utf-8"
+        )
+        .body(nodeinfo_json.to_string().into())
+        .unwrap()
+}
+
+pub async fn host_meta(ctx: Context) -> Response {
+
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+
+    //Older clients find webfinger through the lrdd template
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/xrd+xml; charset=utf-8")
+        .body(
+            format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<XRD xmlns=\"http://docs.oasis-open.org/ns/xri/xrd-1.0\">
+  <Link rel=\"lrdd\" template=\"{}/.well-known/webfinger?resource={{uri}}\"/>
+</XRD>
+",
+                    ap_base_url(),
+            ).into()
+        )
+        .unwrap()
 }
 
 pub async fn podcasts(ctx: Context) -> Response {
@@ -730,7 +941,7 @@ pub async fn podcasts(ctx: Context) -> Response {
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -763,36 +974,27 @@ pub async fn podcasts(ctx: Context) -> Response {
     }
     let podcast_guid = guid.clone();
 
+    //##: People clicking through to a podcast get its profile page instead of the actor json
+    if http_action == "GET" && podcast_guid != "0" && !accepts_activity_json(&ctx) {
+        let mut response = profiles(ctx).await;
+        response.headers_mut().insert("Vary", hyper::header::HeaderValue::from_static("Accept"));
+        return response;
+    }
+
     //##: Lookup API of podcast
     let podcast_data: PIPodcast;
     if podcast_guid != "0" {
-        let api_response = api_get_podcast(
-            &ctx.pi_auth.key,
-            &ctx.pi_auth.secret,
-            &podcast_guid,
-        ).await;
+        let api_response = ctx.pi_client.get_podcast(&podcast_guid).await;
         match api_response {
-            Ok(response_body) => {
-                //eprintln!("{:#?}", response_body);
-                match serde_json::from_str(response_body.as_str()) {
-                    Ok(data) => {
-                        podcast_data = data;
-                        println!("{}", podcast_data.feed.image);
-                    }
-                    Err(e) => {
-                        println!("Response prep error: [{:#?}].\n", e);
-                        return hyper::Response::builder()
-                            .status(StatusCode::from_u16(501).unwrap())
-                            .body(format!("Response prep error.").into())
-                            .unwrap();
-                    }
-                }
+            Ok(data) => {
+                podcast_data = data;
+                println!("{}", podcast_data.feed.image);
             }
             Err(e) => {
                 println!("Response prep error: [{:#?}].\n", e);
                 return hyper::Response::builder()
                     .status(StatusCode::from_u16(501).unwrap())
-                    .body(format!("Response prep error.").into())
+                    .body("Response prep error.".to_string().into())
                     .unwrap();
             }
         }
@@ -814,6 +1016,7 @@ pub async fn podcasts(ctx: Context) -> Response {
                 artwork: "https://noagendaassets.com/enc/1684513486.722_pcifeedimage.png".to_string(),
                 episodeCount: 0,
                 funding: None,
+                podroll: None,
             },
         }
     }
@@ -823,12 +1026,20 @@ pub async fn podcasts(ctx: Context) -> Response {
     match ap_get_actor_keys(podcast_guid.parse::<u64>().unwrap()) {
         Ok(keys) => {
             actor_keys = keys;
+            //##: So podpings for this feed can be matched back to the actor
+            if podcast_data.feed.id != 0 && !podcast_data.feed.url.is_empty() {
+                let _ = dbif::update_actor_feed_url_in_db(
+                    &AP_DATABASE_FILE.to_string(),
+                    podcast_data.feed.id,
+                    podcast_data.feed.url.clone(),
+                );
+            }
         }
         Err(e) => {
             println!("Actor keys retreival error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(500).unwrap())
-                .body(format!("Key error.").into())
+                .body("Key error.".to_string().into())
                 .unwrap();
         }
     }
@@ -839,41 +1050,41 @@ pub async fn podcasts(ctx: Context) -> Response {
         Ok(data) => {
             actor_data = data;
             if no_guid {
-                actor_data.id = "https://ap.podcastindex.org/podcasts".to_string();
+                actor_data.id = format!("{}/podcasts", ap_base_url()).to_string();
             }
             if podcast_guid == "0" {
                 actor_data.r#type = "Application".to_string();
-                actor_data.name = Some("ap.podcastindex.org".to_string());
-                actor_data.preferredUsername = Some("ap.podcastindex.org".to_string());
+                actor_data.name = Some(ap_host());
+                actor_data.preferredUsername = Some(ap_host());
             }
         }
         Err(e) => {
             println!("Actor object build error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(500).unwrap())
-                .body(format!("Actor obect error.").into())
+                .body("Actor obect error.".to_string().into())
                 .unwrap();
         }
     }
-    let actor_json;
-    match serde_json::to_string_pretty(&actor_data) {
+    let actor_json = match serde_json::to_string_pretty(&actor_data) {
         Ok(json_result) => {
-            actor_json = json_result;
+            json_result
         }
         Err(e) => {
             println!("Response prep error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(500).unwrap())
-                .body(format!("Response prep error.").into())
+                .body("Response prep error.".to_string().into())
                 .unwrap();
         }
-    }
+    };
 
-    return hyper::Response::builder()
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("{}", actor_json).into())
-        .unwrap();
+        .header("Vary", "Accept")
+        .body(actor_json.to_string().into())
+        .unwrap()
 }
 
 pub async fn profiles(ctx: Context) -> Response {
@@ -881,7 +1092,7 @@ pub async fn profiles(ctx: Context) -> Response {
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -894,59 +1105,77 @@ pub async fn profiles(ctx: Context) -> Response {
     //println!("Context: {:#?}", ctx);
 
     //Make sure a session param was given
-    let guid;
-    match params.get("id") {
+    let guid = match params.get("id") {
         Some(resource) => {
             println!("  Id: {}\n", resource);
             let parts = resource.replace("acct:", "");
-            guid = parts.split("@").next().unwrap().to_string();
+            parts.split("@").next().unwrap().to_string()
         }
         None => {
             println!("Invalid resource.\n");
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("No resource given.").into())
+                .body("No resource given.".to_string().into())
                 .unwrap();
         }
-    }
+    };
 
     let podcast_guid = guid.clone();
 
+    //##: The latest few episodes get a player on the page
+    let mut episodes_html = "".to_string();
+    if let Ok(episodes_data) = ctx.pi_client.get_episodes(&podcast_guid, AP_PROFILE_EPISODE_COUNT).await {
+        for episode in episodes_data.items.iter() {
+            episodes_html.push_str(ap_build_episode_html(episode, &podcast_guid).as_str());
+        }
+    }
+
     //Lookup API of podcast
     let podcast_data: PIPodcast;
-    let api_response = api_get_podcast(
-        &ctx.pi_auth.key,
-        &ctx.pi_auth.secret,
-        &podcast_guid,
-    ).await;
+    let api_response = ctx.pi_client.get_podcast(&podcast_guid).await;
     match api_response {
-        Ok(response_body) => {
-            //eprintln!("{:#?}", response_body);
-            match serde_json::from_str(response_body.as_str()) {
-                Ok(data) => {
-                    podcast_data = data;
-                    println!("{}", podcast_data.feed.image);
-                }
-                Err(e) => {
-                    println!("Response prep error: [{:#?}].\n", e);
-                    return hyper::Response::builder()
-                        .status(StatusCode::from_u16(501).unwrap())
-                        .body(format!("Response prep error.").into())
-                        .unwrap();
-                }
-            }
+        Ok(data) => {
+            podcast_data = data;
+            println!("{}", podcast_data.feed.image);
         }
         Err(e) => {
             println!("Response prep error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(501).unwrap())
-                .body(format!("Response prep error.").into())
+                .body("Response prep error.".to_string().into())
                 .unwrap();
         }
     }
 
+    //##: Funding and the podcast's own site, which gets rel=me so podcasters can verify the bridge account
+    let mut links_html = "".to_string();
+    if let Some(funding) = &podcast_data.feed.funding {
+        if let Some(funding_url) = funding.url.as_ref().filter(|funding_url| is_web_url(funding_url)) {
+            links_html.push_str(format!(
+                "<li><a href='{}'>{}</a></li>",
+                html_escape(funding_url),
+                html_escape(funding.message.as_deref().unwrap_or("Support the show")),
+            ).as_str());
+        }
+    }
+    let mut rel_me_html = "".to_string();
+    if is_web_url(&podcast_data.feed.link) {
+        rel_me_html = format!("<link rel='me' href='{}' />", html_escape(&podcast_data.feed.link));
+        links_html.push_str(format!(
+            "<li><a rel='me' href='{}'>Website</a></li>",
+            html_escape(&podcast_data.feed.link),
+        ).as_str());
+    }
+
+    let follower_count = dbif::get_follower_count_from_db(
+        &AP_DATABASE_FILE.to_string(),
+        podcast_guid.parse::<u64>().unwrap_or(0),
+    ).unwrap_or(0);
+    let actor_url = format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string();
+    let handle = format!("{}@{}", podcast_guid, ap_host());
+
     //Build HTML profile page
-    return hyper::Response::builder()
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "text/html")
         .body(
@@ -954,6 +1183,7 @@ pub async fn profiles(ctx: Context) -> Response {
 <html lang='en'>
   <head>
     <meta charset='utf-8' />
+    <title>{}</title>
     <meta content='{}' property='og:title' />
     <meta content='{}' property='og:url' />
     <meta content='{}' property='og:description' />
@@ -961,26 +1191,55 @@ pub async fn profiles(ctx: Context) -> Response {
     <meta content='{}' property='og:image' />
     <meta content='150' property='og:image:width' />
     <meta content='150' property='og:image:height' />
+    <link rel='alternate' type='application/activity+json' href='{}' />
+    {}
   </head>
   <body>
-    Empty
+    <header>
+      <img src='{}' alt='{}' width='150' height='150' />
+      <h1>{}</h1>
+      <p>@{}</p>
+      <p>{} followers</p>
+      <form method='get' action='{}/ostatus_subscribe'>
+        <input type='hidden' name='acct' value='{}' />
+        <input type='text' name='handle' placeholder='you@your.instance' />
+        <button type='submit'>Follow</button>
+      </form>
+    </header>
+    <p>{}</p>
+    <ul>{}</ul>
+    <h2>Episodes</h2>
+    {}
   </body>
   </html>",
-                    podcast_data.feed.title,
-                    format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-                    podcast_data.feed.description,
-                    podcast_data.feed.image
+                    html_escape(&podcast_data.feed.title),
+                    html_escape(&podcast_data.feed.title),
+                    html_escape(&actor_url),
+                    html_escape(&html_strip_tags(&podcast_data.feed.description)),
+                    html_escape(&podcast_data.feed.image),
+                    html_escape(&actor_url),
+                    rel_me_html,
+                    html_escape(&podcast_data.feed.image),
+                    html_escape(&podcast_data.feed.title),
+                    html_escape(&podcast_data.feed.title),
+                    html_escape(&handle),
+                    follower_count,
+                    ap_base_url(),
+                    html_escape(&handle),
+                    html_escape(&html_strip_tags(&podcast_data.feed.description)),
+                    links_html,
+                    episodes_html,
             ).into()
         )
-        .unwrap();
+        .unwrap()
 }
 
-pub async fn outbox(ctx: Context) -> Response {
+pub async fn ostatus_subscribe(ctx: Context) -> Response {
 
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -992,23 +1251,125 @@ pub async fn outbox(ctx: Context) -> Response {
     );
     //println!("Context: {:#?}", ctx);
 
-    //Make sure a session param was given
-    let guid;
-    match params.get("id") {
+    //Make sure the account to follow was given.  It can be a handle or the actor url
+    let podcast_guid;
+    match params.get("acct") {
         Some(resource) => {
-            println!("  Id: {}\n", resource);
-            let parts = resource.replace("acct:", "");
-            guid = parts.split("@").next().unwrap().to_string();
+            println!("  Acct: {}\n", resource);
+            if resource.starts_with(ap_base_url()) {
+                podcast_guid = get_id_from_url(resource.to_string());
+            } else {
+                let parts = resource.replace("acct:", "");
+                podcast_guid = parts.trim_start_matches('@').split("@").next().unwrap().to_string();
+            }
         }
         None => {
-            guid = "0".to_string();
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    }
+    if podcast_guid.parse::<u64>().is_err() {
+        println!("Invalid podcast id: [{}].\n", podcast_guid);
+        return hyper::Response::builder()
+            .status(StatusCode::from_u16(400).unwrap())
+            .body("Invalid podcast id.".to_string().into())
+            .unwrap();
+    }
+    let actor_url = format!("{}/podcasts?id={}", ap_base_url(), podcast_guid);
+
+    //##: Once the visitor gives their own handle, send them home to finish the follow there
+    let mut error_message = "".to_string();
+    if let Some(handle) = params.get("handle") {
+        match ap_get_remote_subscribe_url(handle, &actor_url).await {
+            Ok(subscribe_url) => {
+                println!("  Remote follow redirect: [{}]", subscribe_url);
+                return hyper::Response::builder()
+                    .status(StatusCode::FOUND)
+                    .header("Location", subscribe_url)
+                    .body(String::new().into())
+                    .unwrap();
+            }
+            Err(e) => {
+                eprintln!("  Remote follow lookup failed: [{}|{}]", handle, e);
+                error_message = format!("Could not find the account: {}", handle);
+            }
+        }
+    }
+
+    //##: Show the podcast title if the index knows it
+    let mut podcast_title = format!("{}@{}", podcast_guid, ap_host());
+    if let Ok(podcast_data) = ctx.pi_client.get_podcast(&podcast_guid).await {
+        podcast_title = podcast_data.feed.title;
+    }
+
+    //Build HTML remote follow page
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "text/html")
+        .body(
+            format!("<!DOCTYPE html>
+<html lang='en'>
+  <head>
+    <meta charset='utf-8' />
+    <title>Follow {}</title>
+  </head>
+  <body>
+    <h1>Follow {}</h1>
+    <p>{}</p>
+    <form method='get' action='{}/ostatus_subscribe'>
+      <input type='hidden' name='acct' value='{}' />
+      <label for='handle'>Your fediverse handle</label>
+      <input type='text' id='handle' name='handle' placeholder='you@your.instance' />
+      <button type='submit'>Follow</button>
+    </form>
+  </body>
+  </html>",
+                    html_escape(&podcast_title),
+                    html_escape(&podcast_title),
+                    html_escape(&error_message),
+                    ap_base_url(),
+                    html_escape(&format!("{}@{}", podcast_guid, ap_host())),
+            ).into()
+        )
+        .unwrap()
+}
+
+pub async fn outbox(ctx: Context) -> Response {
+
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
+
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+    //println!("Context: {:#?}", ctx);
+
+    //Make sure a session param was given
+    let guid = match params.get("id") {
+        Some(resource) => {
+            println!("  Id: {}\n", resource);
+            let parts = resource.replace("acct:", "");
+            parts.split("@").next().unwrap().to_string()
+        }
+        None => {
+            "0".to_string()
             // println!("Invalid resource.\n");
             // return hyper::Response::builder()
             //     .status(StatusCode::from_u16(400).unwrap())
             //     .body(format!("No resource given.").into())
             //     .unwrap();
         }
-    }
+    };
     let podcast_guid = guid.clone();
 
     let mut paging = false;
@@ -1024,58 +1385,42 @@ pub async fn outbox(ctx: Context) -> Response {
         }
     }
 
-    //Lookup API of podcast
-    let podcast_data: PIEpisodes;
+    //##: Paging cursors are an episode's publish timestamp and id
+    let max_id = params.get("max_id").and_then(|max_id| parse_outbox_cursor(max_id, 0));
+    let min_id = params.get("min_id").and_then(|min_id| parse_outbox_cursor(min_id, u64::MAX));
+
+    //##: The collection size comes from the feed, so the summary doesn't need any episodes.  The instance
+    //##: actor's outbox is a moving window over the most recent episodes across the index, so it has no size
+    let total_items;
     if podcast_guid != "0" {
-        let api_response = api_get_episodes(
-            &ctx.pi_auth.key,
-            &ctx.pi_auth.secret,
-            &podcast_guid,
-        ).await;
-        match api_response {
-            Ok(response_body) => {
-                //eprintln!("{:#?}", response_body);
-                match serde_json::from_str(response_body.as_str()) {
-                    Ok(data) => {
-                        podcast_data = data;
-                    }
-                    Err(e) => {
-                        println!("Response prep error: [{:#?}].\n", e);
-                        return hyper::Response::builder()
-                            .status(StatusCode::from_u16(501).unwrap())
-                            .body(format!("Response prep error.").into())
-                            .unwrap();
-                    }
-                }
+        match ctx.pi_client.get_podcast(&podcast_guid).await {
+            Ok(data) => {
+                total_items = Some(data.feed.episodeCount);
             }
             Err(e) => {
                 println!("Response prep error: [{:#?}].\n", e);
                 return hyper::Response::builder()
                     .status(StatusCode::from_u16(501).unwrap())
-                    .body(format!("Response prep error.").into())
+                    .body("Response prep error.".to_string().into())
                     .unwrap();
             }
         }
     } else {
-        //##: TODO - this should contain all episodes probably
-        podcast_data = PIEpisodes {
-            status: "true".to_string(),
-            items: vec![],
-            count: 0,
-        }
+        total_items = None;
     }
 
+    let outbox_id = format!("{}/outbox?id={}", ap_base_url(), podcast_guid);
 
     //If no page=true was given, just give the outbox configuration
     let outbox_json;
     if !paging {
         let outbox_data = OutboxConfig {
             context: "https://www.w3.org/ns/activitystreams".to_string(),
-            id: format!("https://ap.podcastindex.org/outbox?id={}", podcast_guid).to_string(),
+            id: outbox_id.clone(),
             r#type: "OrderedCollection".to_string(),
-            totalItems: podcast_data.count,
-            first: format!("https://ap.podcastindex.org/outbox?id={}&page=true", podcast_guid).to_string(),
-            last: format!("https://ap.podcastindex.org/outbox?id={}&page=true&min_id=0", podcast_guid).to_string(),
+            totalItems: total_items,
+            first: format!("{}&page=true", outbox_id).to_string(),
+            last: format!("{}&page=true&min_id=0", outbox_id).to_string(),
         };
 
         match serde_json::to_string_pretty(&outbox_data) {
@@ -1086,72 +1431,69 @@ pub async fn outbox(ctx: Context) -> Response {
                 println!("Response prep error: [{:#?}].\n", e);
                 return hyper::Response::builder()
                     .status(StatusCode::from_u16(500).unwrap())
-                    .body(format!("Response prep error.").into())
+                    .body("Response prep error.".to_string().into())
                     .unwrap();
             }
         }
 
         //Otherwise give back a listing of episodes
     } else {
+        //Lookup API of podcast
+        let podcast_data: PIEpisodes = match ap_get_outbox_episodes(ctx.pi_client.as_ref(), &podcast_guid, max_id, min_id).await {
+            Ok(data) => {
+                data
+            }
+            Err(e) => {
+                println!("Response prep error: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(501).unwrap())
+                    .body("Response prep error.".to_string().into())
+                    .unwrap();
+            }
+        };
+
+        let (episodes, has_older, has_newer) = paginate_by_date(
+            podcast_data.items,
+            |episode| (episode.datePublished, episode.id),
+            max_id,
+            min_id,
+            AP_OUTBOX_PAGE_SIZE,
+        );
+
+        //##: Older episodes are walked with max_id, newer ones with min_id
+        let mut page_id = format!("{}&page=true", outbox_id);
+        if let Some(max_id) = params.get("max_id") {
+            page_id = format!("{}&max_id={}", page_id, urlencoding::encode(max_id));
+        } else if let Some(min_id) = params.get("min_id") {
+            page_id = format!("{}&min_id={}", page_id, urlencoding::encode(min_id));
+        }
+        let mut next = None;
+        let mut prev = None;
+        if let (Some(newest), Some(oldest)) = (episodes.first(), episodes.last()) {
+            if has_older {
+                next = Some(format!("{}&page=true&max_id={}_{}", outbox_id, oldest.datePublished, oldest.id));
+            }
+            if has_newer {
+                prev = Some(format!("{}&page=true&min_id={}_{}", outbox_id, newest.datePublished, newest.id));
+            }
+        }
+
         let mut ordered_items = Vec::new();
-        for episode in podcast_data.items {
-            ordered_items.push(Item {
-                id: format!(
-                    "https://ap.podcastindex.org/episodes?id={}&statusid={}&resource=activity",
-                    podcast_guid,
-                    episode.guid
-                ).to_string(),
-                r#type: "Create".to_string(),
-                actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-                published: iso8601(episode.datePublished),
-                directMessage: false,
-                to: vec!(
-                    "https://www.w3.org/ns/activitystreams#Public".to_string()
-                ),
-                object: Object {
-                    id: format!(
-                        "https://ap.podcastindex.org/episodes?id={}&statusid={}&resource=post",
-                        podcast_guid,
-                        episode.guid
-                    ).to_string(),
-                    r#type: "Note".to_string(),
-                    summary: None,
-                    inReplyTo: None,
-                    published: iso8601(episode.datePublished),
-                    url: format!(
-                        "https://ap.podcastindex.org/episodes?id={}&statusid={}&resource=public",
-                        podcast_guid,
-                        episode.guid
-                    ).to_string(),
-                    attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-                    to: vec!(
-                        "https://www.w3.org/ns/activitystreams#Public".to_string()
-                    ),
-                    cc: None,
-                    sensitive: false,
-                    conversation: format!(
-                        "tag:ap.podcastindex.org,{}:objectId={}:objectType=Conversation",
-                        iso8601(episode.datePublished),
-                        episode.guid
-                    ).to_string(),
-                    content: format!(
-                        "<p>{:.128}</p><p>{:.128}</p><p>Listen: {}</p>",
-                        episode.title,
-                        episode.description,
-                        episode.enclosureUrl
-                    ),
-                    attachment: vec!(),
-                },
-            })
+        for episode in episodes {
+            if podcast_guid == "0" {
+                ordered_items.push(ap_build_outbox_item(&episode, "Announce"));
+            } else {
+                ordered_items.push(ap_build_outbox_item(&episode, "Create"));
+            }
         }
         let outbox_data = OutboxPaged {
             context: "https://www.w3.org/ns/activitystreams".to_string(),
-            id: "https://www.w3.org/ns/activitystreams".to_string(),
+            id: page_id,
             r#type: "OrderedCollectionPage".to_string(),
-            next: format!("https://ap.podcastindex.org/outbox?id={}&page=true&max_id=999999", podcast_guid).to_string(),
-            prev: format!("https://ap.podcastindex.org/outbox?id={}&page=true&min_id=0", podcast_guid).to_string(),
-            partOf: format!("https://ap.podcastindex.org/outbox?id={}", podcast_guid).to_string(),
-            totalItems: podcast_data.count,
+            next,
+            prev,
+            partOf: outbox_id.clone(),
+            totalItems: total_items,
             orderedItems: Some(ordered_items),
         };
 
@@ -1163,17 +1505,17 @@ pub async fn outbox(ctx: Context) -> Response {
                 println!("Response prep error: [{:#?}].\n", e);
                 return hyper::Response::builder()
                     .status(StatusCode::from_u16(500).unwrap())
-                    .body(format!("Response prep error.").into())
+                    .body("Response prep error.".to_string().into())
                     .unwrap();
             }
         }
     }
 
-    return hyper::Response::builder()
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("{}", outbox_json).into())
-        .unwrap();
+        .body(outbox_json.to_string().into())
+        .unwrap()
 }
 
 pub async fn inbox(ctx: Context) -> Response {
@@ -1181,7 +1523,7 @@ pub async fn inbox(ctx: Context) -> Response {
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -1194,12 +1536,11 @@ pub async fn inbox(ctx: Context) -> Response {
     //println!("Context: {:#?}", ctx);
 
     //Make sure a podcast id was given
-    let guid;
-    match params.get("id") {
+    let guid = match params.get("id") {
         Some(resource) => {
             println!("  Id: {}\n", resource);
             let parts = resource.replace("acct:", "");
-            guid = parts.split("@").next().unwrap().to_string();
+            parts.split("@").next().unwrap().to_string()
         }
         None => {
             eprintln!("Invalid resource: [{}]: [{}] from: {:#?}",
@@ -1209,73 +1550,113 @@ pub async fn inbox(ctx: Context) -> Response {
             );
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("No resource given.").into())
+                .body("No resource given.".to_string().into())
                 .unwrap();
         }
-    }
+    };
     let podcast_guid = guid.clone();
 
-    let (_parts, body) = ctx.req.into_parts();
+    let (parts, body) = ctx.req.into_parts();
     let body_bytes = hyper::body::to_bytes(body).await.unwrap();
     let body = std::str::from_utf8(&body_bytes).unwrap();
 
     //##: Decode the inbox request JSON
-    let incoming_data;
-    match serde_json::from_str::<InboxRequestWithObject>(body) {
+    let incoming_data = match serde_json::from_str::<InboxRequestWithObject>(body) {
         Ok(decoded_inbox_request) => {
-            //TODO: validate the key signature before accepting request
-            incoming_data = decoded_inbox_request;
+            decoded_inbox_request
         }
         Err(e) => {
             eprintln!("Could not parse incoming request: [{}].\n", e);
             eprintln!("  BODY: {}", body);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("Invalid request format.").into())
+                .body("Invalid request format.".to_string().into())
                 .unwrap();
         }
-    }
+    };
+
+    //##: Validate the http signature before accepting the request
+    let signing_key = match ap_verify_request_signature(
+        podcast_guid.parse::<u64>().unwrap_or(0),
+        &parts,
+        body,
+        incoming_data.actor.clone().unwrap_or("".to_string()),
+    ).await {
+        Ok(remote_key) => {
+            println!("  Signature verified for: [{}] with key: [{}]", remote_key.owner, remote_key.keyid);
+            remote_key
+        }
+        Err(e) => {
+            eprintln!("Signature verification failed: [{}] from: {:#?}", e, incoming_data.actor);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(401).unwrap())
+                .body("Invalid signature.".to_string().into())
+                .unwrap();
+        }
+    };
 
     //##: Handle the request based on its type
     match incoming_data.r#type.to_lowercase().as_str() {
         "delete" => {
-            //println!("--Delete request: {:#?}", incoming_data);
-            //println!("  BODY: {}", body);
+            println!("--Delete request");
+            let deleting_actor = incoming_data.actor.clone().unwrap_or("".to_string());
+
+            //##: An actor deleting itself takes its follows and replies with it
+            if incoming_data.object.id == deleting_actor {
+                println!("  Actor deleted: [{}]", deleting_actor);
+                let _ = dbif::remove_follower_from_db_by_actor(&AP_DATABASE_FILE.to_string(), deleting_actor.clone());
+                let _ = dbif::remove_replies_from_db_by_actor(&AP_DATABASE_FILE.to_string(), deleting_actor.clone());
+                let _ = dbif::remove_likes_from_db_by_actor(&AP_DATABASE_FILE.to_string(), deleting_actor.clone());
+                let _ = dbif::remove_shares_from_db_by_actor(&AP_DATABASE_FILE.to_string(), deleting_actor);
+            } else {
+                println!("  Object deleted: [{}]", incoming_data.object.id);
+                let _ = dbif::remove_reply_from_db(&AP_DATABASE_FILE.to_string(), ReplyRecord {
+                    pcid: 0,
+                    statusid: "".to_string(),
+                    objectid: incoming_data.object.id.clone(),
+                    objecttype: "".to_string(),
+                    attributedto: deleting_actor,
+                    content: "".to_string(),
+                    sensitive: 0,
+                    published: "".to_string(),
+                    received: 0,
+                    conversation: "".to_string(),
+                    edited: 0,
+                });
+            }
         }
 
         "follow" => {
             println!("--Follow request");
             println!("  FROM: [{}]", incoming_data.actor.clone().unwrap());
-            match ap_block_get_remote_actor(podcast_guid.parse::<u64>().unwrap(), incoming_data.actor.clone().unwrap()) {
+            match ap_get_remote_actor(podcast_guid.parse::<u64>().unwrap(), incoming_data.actor.clone().unwrap()).await {
                 Ok(remote_actor) => {
                     //##: Construct a response
                     println!("  Building follow accept json.");
-                    let accept_data;
-                    match ap_build_follow_accept(incoming_data, podcast_guid.parse::<u64>().unwrap()) {
+                    let accept_data = match ap_build_follow_accept(incoming_data, podcast_guid.parse::<u64>().unwrap()) {
                         Ok(data) => {
-                            accept_data = data;
+                            data
                         }
                         Err(e) => {
                             eprintln!("Build follow accept error: [{:#?}].\n", e);
                             return hyper::Response::builder()
                                 .status(StatusCode::from_u16(500).unwrap())
-                                .body(format!("Accept build error.").into())
+                                .body("Accept build error.".to_string().into())
                                 .unwrap();
                         }
-                    }
-                    let _accept_json;
-                    match serde_json::to_string_pretty(&accept_data) {
+                    };
+                    let _accept_json = match serde_json::to_string_pretty(&accept_data) {
                         Ok(json_result) => {
-                            _accept_json = json_result;
+                            json_result
                         }
                         Err(e) => {
                             eprintln!("Response prep error: [{:#?}].\n", e);
                             return hyper::Response::builder()
                                 .status(StatusCode::from_u16(500).unwrap())
-                                .body(format!("Accept encode error.").into())
+                                .body("Accept encode error.".to_string().into())
                                 .unwrap();
                         }
-                    }
+                    };
 
                     //##: Send the accept request to the follower inbox url
                     println!("  Send the follow accept request.");
@@ -1286,21 +1667,20 @@ pub async fn inbox(ctx: Context) -> Response {
                     ) {
                         Ok(_) => {
                             let instance_fqdn = get_host_from_url(remote_actor.inbox.clone());
-                            let shared_inbox;
-                            match remote_actor.endpoints {
+                            let shared_inbox = match remote_actor.endpoints {
                                 Some(endpoints) => {
-                                    shared_inbox = endpoints.sharedInbox.clone();
+                                    endpoints.sharedInbox.clone()
                                 }
                                 None => {
-                                    shared_inbox = remote_actor.inbox.clone();
+                                    remote_actor.inbox.clone()
                                 }
-                            }
+                            };
                             match dbif::add_follower_to_db(&AP_DATABASE_FILE.to_string(), FollowerRecord {
                                 pcid: podcast_guid.parse::<u64>().unwrap(),
                                 actor: remote_actor.id.clone(),
                                 instance: instance_fqdn,
                                 inbox: remote_actor.inbox,
-                                shared_inbox: shared_inbox,
+                                shared_inbox,
                                 status: "active".to_string(),
                             }) {
                                 Ok(_) => {
@@ -1310,7 +1690,7 @@ pub async fn inbox(ctx: Context) -> Response {
                                     eprintln!("Unable to save follow state: [{}].\n", e);
                                     return hyper::Response::builder()
                                         .status(StatusCode::from_u16(400).unwrap())
-                                        .body(format!("Unable to save follow state.").into())
+                                        .body("Unable to save follow state.".to_string().into())
                                         .unwrap();
                                 }
                             }
@@ -1319,7 +1699,7 @@ pub async fn inbox(ctx: Context) -> Response {
                             eprintln!("Acknowledging failed: [{}].\n", e);
                             return hyper::Response::builder()
                                 .status(StatusCode::from_u16(400).unwrap())
-                                .body(format!("Acknowledging failed.").into())
+                                .body("Acknowledging failed.".to_string().into())
                                 .unwrap();
                         }
                     };
@@ -1328,33 +1708,184 @@ pub async fn inbox(ctx: Context) -> Response {
                     eprintln!("Bad actor: [{}].\n", e);
                     return hyper::Response::builder()
                         .status(StatusCode::from_u16(400).unwrap())
-                        .body(format!("Bad actor.").into())
+                        .body("Bad actor.".to_string().into())
                         .unwrap();
                 }
             }
         }
 
-        "undo" => {
-            println!("--Unfollow request");
-            if incoming_data.object.r#type.is_some()
-                && incoming_data.object.r#type.as_ref().unwrap().to_lowercase() == "follow"
-            {
-                let _ = dbif::remove_follower_from_db(&AP_DATABASE_FILE.to_string(), FollowerRecord {
-                    pcid: podcast_guid.parse::<u64>().unwrap(),
-                    actor: incoming_data.actor.unwrap(),
-                    instance: "".to_string(),
-                    inbox: "".to_string(),
-                    shared_inbox: "".to_string(),
-                    status: "".to_string(),
+        "update" => {
+            println!("--Update request");
+            let updating_actor = incoming_data.actor.clone().unwrap_or("".to_string());
+
+            //##: A profile update, so refresh where we deliver to them
+            if incoming_data.object.id == updating_actor {
+                println!("  Actor updated: [{}]", updating_actor);
+                match ap_get_remote_actor(podcast_guid.parse::<u64>().unwrap_or(0), updating_actor.clone()).await {
+                    Ok(remote_actor) => {
+                        let shared_inbox = match remote_actor.endpoints {
+                            Some(endpoints) => {
+                                endpoints.sharedInbox.clone()
+                            }
+                            None => {
+                                remote_actor.inbox.clone()
+                            }
+                        };
+                        //##: They may have rotated their key, so the one we hold for them is replaced too.  Only
+                        //##: take it from the actor that was asked for, and only under the key id that signed this Update
+                        let key_check = if remote_actor.id != updating_actor {
+                            Err(Box::new(HydraError(format!(
+                                "Fetched actor: [{}] is not the updating actor: [{}]",
+                                remote_actor.id,
+                                updating_actor
+                            ))) as Box<dyn Error>)
+                        } else {
+                            ap_check_remote_key_owner(
+                                &signing_key.keyid,
+                                &remote_actor.id,
+                                &remote_actor.publicKey.id,
+                                &remote_actor.publicKey.owner,
+                            )
+                        };
+                        match key_check {
+                            Ok(_) => {
+                                let _ = dbif::replace_remote_key_in_db(&AP_DATABASE_FILE.to_string(), RemoteKeyRecord {
+                                    keyid: remote_actor.publicKey.id.clone(),
+                                    owner: remote_actor.publicKey.owner.clone(),
+                                    pem_public_key: remote_actor.publicKey.publicKeyPem.clone(),
+                                    fetched: SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs(),
+                                });
+                            }
+                            Err(e) => {
+                                eprintln!("  Not keeping updated actor key: [{}]", e);
+                            }
+                        }
+
+                        //##: The inbox is whatever their server says it is, so don't take it unless it's a real url
+                        match url::Url::parse(&remote_actor.inbox).ok().and_then(|inbox| inbox.host_str().map(String::from)) {
+                            Some(instance) => {
+                                let _ = dbif::update_follower_inboxes_in_db(&AP_DATABASE_FILE.to_string(), FollowerRecord {
+                                    pcid: 0,
+                                    actor: updating_actor,
+                                    instance,
+                                    inbox: remote_actor.inbox,
+                                    shared_inbox,
+                                    status: "".to_string(),
+                                });
+                            }
+                            None => {
+                                eprintln!("  Updated actor has a bad inbox: [{}]", remote_actor.inbox);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("  Can't get updated actor: [{:#?}]", e);
+                    }
+                }
+
+            //##: An edited reply
+            } else if incoming_data.object.content.is_some() {
+                println!("  Object updated: [{}]", incoming_data.object.id);
+                let _ = dbif::update_reply_in_db(&AP_DATABASE_FILE.to_string(), ReplyRecord {
+                    pcid: 0,
+                    statusid: "".to_string(),
+                    objectid: incoming_data.object.id.clone(),
+                    objecttype: "".to_string(),
+                    attributedto: updating_actor,
+                    content: incoming_data.object.content.clone().unwrap(),
+                    sensitive: incoming_data.object.sensitive.unwrap_or(false) as u64,
+                    published: "".to_string(),
+                    received: 0,
+                    conversation: "".to_string(),
+                    edited: SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs(),
                 });
             }
         }
 
+        "like" | "announce" => {
+            println!("--{} request", incoming_data.r#type);
+
+            //##: Only reactions to our own episode notes are kept
+            let object_url = incoming_data.object.id.clone();
+            let mut pcid = 0;
+            let mut statusid = "".to_string();
+            if object_url.starts_with(ap_base_url()) {
+                pcid = get_id_from_url(object_url.clone()).parse::<u64>().unwrap_or(0);
+                statusid = get_statusid_from_url(object_url.clone());
+            }
+            if pcid != 0 && !statusid.is_empty() {
+                let reaction = ReactionRecord {
+                    pcid,
+                    statusid,
+                    actor: incoming_data.actor.clone().unwrap_or("".to_string()),
+                    activityid: incoming_data.id.clone(),
+                    received: SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs(),
+                };
+                if incoming_data.r#type.to_lowercase() == "like" {
+                    let _ = dbif::add_like_to_db(&AP_DATABASE_FILE.to_string(), reaction);
+                } else {
+                    let _ = dbif::add_share_to_db(&AP_DATABASE_FILE.to_string(), reaction);
+                }
+            } else {
+                println!("  Not one of our episodes: [{}]", object_url);
+            }
+        }
+
+        "undo" => {
+            let undone_type = incoming_data.object.r#type.clone().unwrap_or("".to_string()).to_lowercase();
+            let reaction = ReactionRecord {
+                pcid: 0,
+                statusid: "".to_string(),
+                actor: incoming_data.actor.clone().unwrap_or("".to_string()),
+                activityid: incoming_data.object.id.clone(),
+                received: 0,
+            };
+            match undone_type.as_str() {
+                "follow" => {
+                    println!("--Unfollow request");
+                    let _ = dbif::remove_follower_from_db(&AP_DATABASE_FILE.to_string(), FollowerRecord {
+                        pcid: podcast_guid.parse::<u64>().unwrap(),
+                        actor: incoming_data.actor.unwrap(),
+                        instance: "".to_string(),
+                        inbox: "".to_string(),
+                        shared_inbox: "".to_string(),
+                        status: "".to_string(),
+                    });
+                }
+                "like" => {
+                    println!("--Unlike request");
+                    let _ = dbif::remove_like_from_db(&AP_DATABASE_FILE.to_string(), reaction);
+                }
+                "announce" => {
+                    println!("--Unboost request");
+                    let _ = dbif::remove_share_from_db(&AP_DATABASE_FILE.to_string(), reaction);
+                }
+                _ => {
+                    println!("--Unhandled undo request: {:#?}", incoming_data);
+                }
+            }
+        }
+
         "create" => {
             //##: Create
             println!("--Create request: {:#?}", incoming_data);
             println!("  BODY: {}", body);
 
+            //##: Replies are kept under whoever signed for them, since that's what edits and deletes are checked
+            //##: against.  An object claiming to be somebody else's would let the signer act in their name
+            let creating_actor = incoming_data.actor.clone().unwrap_or("".to_string());
+            if incoming_data.object.attributedTo.as_ref().is_some_and(|attributed_to| *attributed_to != creating_actor) {
+                eprintln!(
+                    "Object attributed to: [{:?}] was sent by: [{}]",
+                    incoming_data.object.attributedTo,
+                    creating_actor
+                );
+                return hyper::Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body("Object is not attributed to the sending actor.".to_string().into())
+                    .unwrap();
+            }
+
             //##: Replies
             //##: Parse out the inReplyTo so we can determine which podcast this belongs to
             if incoming_data.object.inReplyTo.is_some()
@@ -1366,19 +1897,20 @@ pub async fn inbox(ctx: Context) -> Response {
                 let received_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs();
                 let reply_conversation = incoming_data.object.conversation.unwrap_or("".to_string());
 
-                if parent_pcid != "" && parent_episode_guid != ""
+                if !parent_pcid.is_empty() && !parent_episode_guid.is_empty()
                 {
                     let _ = dbif::add_reply_to_db(&AP_DATABASE_FILE.to_string(), ReplyRecord {
                         pcid: parent_pcid.parse::<u64>().unwrap(),
                         statusid: parent_episode_guid,
                         objectid: incoming_data.object.id.clone(),
                         objecttype: incoming_data.object.r#type.unwrap_or("".to_string()),
-                        attributedto: incoming_data.object.attributedTo.clone().unwrap_or("".to_string()),
+                        attributedto: creating_actor.clone(),
                         content: incoming_data.object.content.clone().unwrap(),
                         sensitive: 0,
                         published: incoming_data.object.published.unwrap_or(received_time.to_string()),
                         received: received_time,
                         conversation: reply_conversation.clone(),
+                        edited: 0,
                     });
                 } else {
                     let replies = dbif::get_a_reply_by_conversation(
@@ -1386,31 +1918,32 @@ pub async fn inbox(ctx: Context) -> Response {
                         reply_conversation.clone(),
                     );
 
-                    for reply in replies.unwrap() {
+                    if let Some(reply) = replies.unwrap().into_iter().next() {
                         let _ = dbif::add_reply_to_db(&AP_DATABASE_FILE.to_string(), ReplyRecord {
                             pcid: reply.pcid,
                             statusid: reply.statusid,
                             objectid: incoming_data.object.id.clone(),
                             objecttype: incoming_data.object.r#type.unwrap_or("".to_string()),
-                            attributedto: incoming_data.object.attributedTo.clone().unwrap_or("".to_string()),
+                            attributedto: creating_actor.clone(),
                             content: incoming_data.object.content.clone().unwrap(),
                             sensitive: 0,
                             published: incoming_data.object.published.unwrap_or(received_time.to_string()),
                             received: received_time,
                             conversation: reply_conversation.clone(),
+                            edited: 0,
                         });
-                        break;
                     }
                 }
             }
 
             //##: PI Action Requests
             //##: We only deem the request an action if it's direct to the actor and not a reply
-            if incoming_data.object.cc.is_some()
-                && incoming_data.object.inReplyTo.is_none()
-                && incoming_data.object.content.is_some()
-            {
-                for cc in incoming_data.object.cc.unwrap() {
+            if let (Some(ccs), None, Some(_)) = (
+                &incoming_data.object.cc,
+                &incoming_data.object.inReplyTo,
+                &incoming_data.object.content,
+            ) {
+                for cc in ccs.clone() {
                     let mut parent_pcid = 0;
 
                     //##: Check for a valid podcast id
@@ -1422,7 +1955,7 @@ pub async fn inbox(ctx: Context) -> Response {
                             eprintln!("Failed to ascertain podcast id: [{}].\n", e);
                         }
                     }
-                    if parent_pcid <= 0 {
+                    if parent_pcid == 0 {
                         continue;
                     }
 
@@ -1432,17 +1965,13 @@ pub async fn inbox(ctx: Context) -> Response {
                         //##: Re-scan action
                         x if x.contains("rescan") => {
                             println!("  PI Action request: [{}|{}].", x, parent_pcid);
-                            let _ = api_hub_rescan(
-                                &ctx.pi_auth.key,
-                                &ctx.pi_auth.secret,
-                                &parent_pcid.to_string().as_str(),
-                            ).await;
-
-                            //##: If this request came from an actor, look them up and reply back
-                            if incoming_data.object.attributedTo.clone().is_some() {
-                                match ap_block_get_remote_actor(parent_pcid.clone(), incoming_data.object.attributedTo.clone().unwrap()) {
+                            let _ = ctx.pi_client.hub_rescan(&parent_pcid.to_string()).await;
+
+                            //##: Reply back to the actor who signed the request
+                            if !creating_actor.is_empty() {
+                                match ap_get_remote_actor(parent_pcid, creating_actor.clone()).await {
                                     Ok(sending_actor) => {
-                                        let _ = ap_block_send_note(
+                                        let _ = ap_send_note(
                                             parent_pcid,
                                             sending_actor.inbox,
                                             "Done.".to_string(),
@@ -1458,50 +1987,31 @@ pub async fn inbox(ctx: Context) -> Response {
 
                         //##: Send me the latest episode
                         x if x.contains("latest") => {
-                            //##: If this request came from an actor, look them up and reply back
-                            if incoming_data.object.attributedTo.clone().is_some() {
-                                match ap_block_get_remote_actor(parent_pcid.clone(), incoming_data.object.attributedTo.clone().unwrap()) {
-                                    Ok(sending_actor) => {
-                                        match api_block_get_episodes(
-                                            &ctx.pi_auth.key,
-                                            &ctx.pi_auth.secret,
-                                            &parent_pcid.to_string().as_str(),
-                                        ) {
-                                            Ok(response_body) => {
-                                                match serde_json::from_str(response_body.as_str()) {
-                                                    Ok(data) => {
-                                                        let podcast_data: PIEpisodes = data;
-                                                        //##: TODO Get this code out of this deep level of nesting
-                                                        let latest_episode = podcast_data.items.get(0);
-                                                        if latest_episode.is_some() {
-                                                            let latest_episode_details = latest_episode.unwrap();
-
-                                                            let _ = ap_block_send_episode_note(
-                                                                parent_pcid,
-                                                                latest_episode_details,
-                                                                sending_actor.inbox,
-                                                                true,
-                                                                Some(incoming_data.object.id),
-                                                            );
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        //##: TODO - refactor this deep nesting
-                                                        eprintln!("  API response prep error: [{:#?}] actor guid: [{}].\n",
-                                                                  e,
-                                                                  parent_pcid.to_string().as_str()
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                            Err(e) => {
-                                                //##: TODO - refactor this deep nesting
-                                                eprintln!("  PI API call error: [{:#?}].\n", e);
-                                            }
-                                        }
+                            //##: Reply back to the actor who signed the request
+                            if !creating_actor.is_empty() {
+                                let sending_actor = match ap_get_remote_actor(parent_pcid, creating_actor.clone()).await {
+                                    Ok(remote_actor) => {
+                                        remote_actor
                                     }
                                     Err(e) => {
                                         println!("  Can't get actor from action request: [{:#?}|\n{}]", e, parent_pcid);
+                                        break;
+                                    }
+                                };
+                                match ctx.pi_client.get_episodes(&parent_pcid.to_string(), 1).await {
+                                    Ok(podcast_data) => {
+                                        if let Some(latest_episode_details) = podcast_data.items.first() {
+                                            let _ = ap_send_episode_note(
+                                                parent_pcid,
+                                                latest_episode_details,
+                                                sending_actor.inbox,
+                                                true,
+                                                Some(incoming_data.object.id),
+                                            );
+                                        }
+                                    }
+                                    Err(e) => {
+                                        eprintln!("  PI API call error: [{:#?}].\n", e);
                                     }
                                 }
                             }
@@ -1524,11 +2034,115 @@ pub async fn inbox(ctx: Context) -> Response {
         }
     }
 
-    return hyper::Response::builder()
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/activity+json; charset=utf-8")
+        .body(String::new().into())
+        .unwrap()
+}
+
+pub async fn following(ctx: Context) -> Response {
+
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
+
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+    //println!("Context: {:#?}", ctx);
+
+    //Make sure a podcast id was given
+    let podcast_guid = match params.get("id").and_then(|id| id.parse::<u64>().ok()) {
+        Some(pcid) => {
+            println!("  Id: {}\n", pcid);
+            pcid
+        }
+        None => {
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    //##: A podcast follows the shows it recommends in its podroll.  The instance actor follows nobody
+    let mut ordered_items = Vec::new();
+    if podcast_guid != 0 {
+        let podcast_data: PIPodcast = match ctx.pi_client.get_podcast(&podcast_guid.to_string()).await {
+            Ok(data) => {
+                data
+            }
+            Err(PIError::NotFound(e)) => {
+                println!("Podcast not found: [{}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(404).unwrap())
+                    .body("Podcast not found.".to_string().into())
+                    .unwrap();
+            }
+            Err(e) => {
+                println!("Response prep error: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(501).unwrap())
+                    .body("Response prep error.".to_string().into())
+                    .unwrap();
+            }
+        };
+
+        //##: Map each recommended feed guid to its bridged actor, skipping ones the index doesn't know
+        for remote_item in podcast_data.feed.podroll.unwrap_or_default() {
+            match ctx.pi_client.get_podcast_by_guid(&remote_item.feedGuid).await {
+                Ok(recommended_podcast) => {
+                    let actor_url = format!("{}/podcasts?id={}", ap_base_url(), recommended_podcast.feed.id);
+                    if !ordered_items.contains(&actor_url) {
+                        ordered_items.push(actor_url);
+                    }
+                }
+                Err(PIError::NotFound(_)) => {
+                    println!("  Podroll feed not in the index: [{}]", remote_item.feedGuid);
+                }
+                Err(e) => {
+                    eprintln!("  Podroll lookup error: [{:#?}|{}].\n", e, remote_item.feedGuid);
+                }
+            }
+        }
+    }
+
+    let following_data = OrderedCollection {
+        at_context: Some("https://www.w3.org/ns/activitystreams".to_string()),
+        id: format!("{}/following?id={}", ap_base_url(), podcast_guid),
+        r#type: "OrderedCollection".to_string(),
+        totalItems: ordered_items.len() as u64,
+        first: None,
+        last: None,
+        orderedItems: Some(ordered_items),
+    };
+
+    let following_json = match serde_json::to_string_pretty(&following_data) {
+        Ok(json_result) => {
+            json_result
+        }
+        Err(e) => {
+            println!("Response prep error: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Response prep error.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("").into())
-        .unwrap();
+        .body(following_json.to_string().into())
+        .unwrap()
 }
 
 pub async fn featured(ctx: Context) -> Response {
@@ -1536,7 +2150,7 @@ pub async fn featured(ctx: Context) -> Response {
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -1549,50 +2163,52 @@ pub async fn featured(ctx: Context) -> Response {
     //println!("Context: {:#?}", ctx);
 
     //Make sure a session param was given
-    let guid;
-    match params.get("id") {
+    let guid = match params.get("id") {
         Some(resource) => {
             println!("  Id: {}\n", resource);
             let parts = resource.replace("acct:", "");
-            guid = parts.split("@").next().unwrap().to_string();
+            parts.split("@").next().unwrap().to_string()
         }
         None => {
             println!("Invalid resource.\n");
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("No resource given.").into())
+                .body("No resource given.".to_string().into())
                 .unwrap();
         }
-    }
+    };
     let podcast_guid = guid.clone();
 
     //If no page=true was given, just give the outbox configuration
-    let outbox_json;
     let mut ordered_items = Vec::new();
     ordered_items.push(FeaturedItem {
         at_context: vec!(
             "https://www.w3.org/ns/activitystreams".to_string(),
         ),
-        actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+        actor: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
         attachment: vec!(),
-        attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+        attributedTo: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
         cc: Some(vec!(
             format!(
-                "https://ap.podcastindex.org/followers?id={}",
+                "{}/followers?id={}",
+                ap_base_url(),
                 podcast_guid
             ).to_string()
         )),
         content: "This account is a podcast.  Follow to see new episodes.".to_string(),
         context: format!(
-            "https://ap.podcastindex.org/contexts?id={}&statusid=0",
+            "{}/contexts?id={}&statusid=0",
+            ap_base_url(),
             podcast_guid
         ).to_string(),
         conversation: format!(
-            "https://ap.podcastindex.org/contexts?id={}&statusid=0",
+            "{}/contexts?id={}&statusid=0",
+            ap_base_url(),
             podcast_guid
         ).to_string(),
         id: format!(
-            "https://ap.podcastindex.org/episodes?id={}&statusid=0",
+            "{}/episodes?id={}&statusid=0",
+            ap_base_url(),
             podcast_guid
         ).to_string(),
         published: "2023-11-09T15:56:28.495803Z".to_string(),
@@ -1616,25 +2232,25 @@ pub async fn featured(ctx: Context) -> Response {
         orderedItems: Some(ordered_items),
     };
 
-    match serde_json::to_string_pretty(&outbox_data) {
+    let outbox_json = match serde_json::to_string_pretty(&outbox_data) {
         Ok(json_result) => {
-            outbox_json = json_result;
+            json_result
         }
         Err(e) => {
             println!("Response prep error: [{:#?}].\n", e);
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(500).unwrap())
-                .body(format!("Response prep error.").into())
+                .body("Response prep error.".to_string().into())
                 .unwrap();
         }
-    }
+    };
 
 
-    return hyper::Response::builder()
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("{}", outbox_json).into())
-        .unwrap();
+        .body(outbox_json.to_string().into())
+        .unwrap()
 }
 
 pub async fn episodes(ctx: Context) -> Response {
@@ -1642,7 +2258,7 @@ pub async fn episodes(ctx: Context) -> Response {
     //Get query parameters
     let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
         url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
-    }).unwrap_or_else(HashMap::new);
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -1655,47 +2271,62 @@ pub async fn episodes(ctx: Context) -> Response {
     //println!("Context: {:#?}", ctx);
 
     //Make sure a session param was given
-    let podcast_guid;
-    match params.get("id") {
+    let podcast_guid = match params.get("id") {
         Some(resource) => {
             println!("  Id: {}\n", resource);
             let parts = resource.replace("acct:", "");
-            podcast_guid = parts.split("@").next().unwrap().to_string();
+            parts.split("@").next().unwrap().to_string()
         }
         None => {
             println!("Invalid resource.\n");
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("No resource given.").into())
+                .body("No resource given.".to_string().into())
                 .unwrap();
         }
-    }
+    };
 
     //Get an episode guid, which will be a status
-    let episode_guid;
-    match params.get("statusid") {
+    let episode_guid = match params.get("statusid") {
         Some(resource) => {
             println!("  Status Id: {}\n", resource);
-            episode_guid = resource;
+            resource
         }
         None => {
             println!("Invalid status id.\n");
             return hyper::Response::builder()
                 .status(StatusCode::from_u16(400).unwrap())
-                .body(format!("No status id given.").into())
+                .body("No status id given.".to_string().into())
                 .unwrap();
         }
-    }
+    };
+
+    //##: People get a web page, servers get the AS2 object
+    let wants_html = !accepts_activity_json(&ctx);
 
     //If the status id was zero, then this is the pinned post
     let episode_json;
     if episode_guid == "0" {
+        if wants_html {
+            return hyper::Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-type", "text/html")
+                .header("Vary", "Accept")
+                .body(ap_build_status_page_html(
+                    &podcast_guid,
+                    "This account is a podcast.",
+                    "<p>This account is a podcast.  Follow to see new episodes.</p>",
+                ).into())
+                .unwrap();
+        }
+
         let episode_data = Status {
             at_context: vec!(
                 "https://www.w3.org/ns/activitystreams".to_string(),
             ),
             id: format!(
-                "https://ap.podcastindex.org/episodes?id={}&statusid=0",
+                "{}/episodes?id={}&statusid=0",
+                ap_base_url(),
                 podcast_guid
             ).to_string(),
             r#type: "Note".to_string(),
@@ -1703,26 +2334,30 @@ pub async fn episodes(ctx: Context) -> Response {
             inReplyTo: None,
             published: "2023-11-09T15:56:28.495803Z".to_string(),
             url: None,
-            attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+            attributedTo: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
             to: vec!(
                 "https://www.w3.org/ns/activitystreams#Public".to_string()
             ),
             cc: Some(vec!(
                 format!(
-                    "https://ap.podcastindex.org/followers?id={}",
+                    "{}/followers?id={}",
+                    ap_base_url(),
                     podcast_guid
                 ).to_string()
             )),
             sensitive: false,
             conversation: format!(
-                "https://ap.podcastindex.org/contexts?id={}&statusid=0",
+                "{}/contexts?id={}&statusid=0",
+                ap_base_url(),
                 podcast_guid
             ).to_string(),
             content: "This account is a podcast.  Follow to see new episodes.".to_string(),
             attachment: Some(vec!()),
-            actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+            actor: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
             tag: vec!(),
-            replies: None,
+            replies: ap_build_replies_collection(podcast_guid.parse::<u64>().unwrap_or(0), episode_guid).ok(),
+            likes: ap_build_reaction_collection("likes", podcast_guid.parse::<u64>().unwrap_or(0), episode_guid).ok(),
+            shares: ap_build_reaction_collection("shares", podcast_guid.parse::<u64>().unwrap_or(0), episode_guid).ok(),
         };
 
         match serde_json::to_string_pretty(&episode_data) {
@@ -1733,118 +2368,126 @@ pub async fn episodes(ctx: Context) -> Response {
                 println!("Response prep error: [{:#?}].\n", e);
                 return hyper::Response::builder()
                     .status(StatusCode::from_u16(500).unwrap())
-                    .body(format!("Response prep error.").into())
+                    .body("Response prep error.".to_string().into())
                     .unwrap();
             }
         }
     } else {
         //##: Lookup episode in the PI API
-        let pi_data: PIEpisode;
-        let api_response = api_get_episode(
-            &ctx.pi_auth.key,
-            &ctx.pi_auth.secret,
-            &podcast_guid,
-            &episode_guid,
-        ).await;
-
-        match api_response {
-            Ok(response_body) => {
-                //eprintln!("{:#?}", response_body);
-                match serde_json::from_str(response_body.as_str()) {
-                    Ok(data) => {
-                        pi_data = data;
-
-                        let timestamp_param = format!(
-                            "&ts={}",
-                            pi_data.episode.datePublished
-                        );
-                        let episode_object = build_episode_note_object(
-                            &pi_data.episode,
-                            podcast_guid.parse::<u64>().unwrap(),
-                            "Title".to_string(),
-                            timestamp_param
-                        ).unwrap();
-
-                        let episode_data = Status {
-                            at_context: vec!(
-                                "https://www.w3.org/ns/activitystreams".to_string(),
-                            ),
-                            id: format!(
-                                "https://ap.podcastindex.org/episodes?id={}&statusid={}",
-                                podcast_guid,
-                                episode_guid
-                            ).to_string(),
-                            r#type: "Note".to_string(),
-                            summary: None,
-                            inReplyTo: None,
-                            published: iso8601(pi_data.episode.datePublished.clone()),
-                            url: None,
-                            attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-                            to: vec!(
-                                "https://www.w3.org/ns/activitystreams#Public".to_string()
-                            ),
-                            cc: Some(vec!(
-                                format!(
-                                    "https://ap.podcastindex.org/followers?id={}",
-                                    podcast_guid
-                                ).to_string()
-                            )),
-                            sensitive: false,
-                            conversation: format!(
-                                "https://ap.podcastindex.org/contexts?id={}&statusid={}",
-                                podcast_guid,
-                                episode_guid
-                            ).to_string(),
-                            content: episode_object.content,
-                            attachment: Some(episode_object.attachment),
-                            actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-                            tag: vec!(),
-                            replies: None,
-                        };
-
-                        //##: Are there replies to include?
-
-
-                        match serde_json::to_string_pretty(&episode_data) {
-                            Ok(json_result) => {
-                                episode_json = json_result;
-                            }
-                            Err(e) => {
-                                println!("Response prep error: [{:#?}].\n", e);
-                                return hyper::Response::builder()
-                                    .status(StatusCode::from_u16(500).unwrap())
-                                    .body(format!("Response prep error.").into())
-                                    .unwrap();
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        println!("Response prep error: [{:#?}].\n", e);
-                        return hyper::Response::builder()
-                            .status(StatusCode::from_u16(501).unwrap())
-                            .body(format!("Response prep error.").into())
-                            .unwrap();
-                    }
-                }
+        let pi_data: PIEpisode = match ctx.pi_client.get_episode(&podcast_guid, episode_guid).await {
+            Ok(data) => {
+                data
             }
             Err(e) => {
                 println!("Response prep error: [{:#?}].\n", e);
                 return hyper::Response::builder()
                     .status(StatusCode::from_u16(501).unwrap())
-                    .body(format!("Response prep error.").into())
+                    .body("Response prep error.".to_string().into())
+                    .unwrap();
+            }
+        };
+
+        if wants_html {
+            return hyper::Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-type", "text/html")
+                .header("Vary", "Accept")
+                .body(ap_build_episode_page_html(&podcast_guid, episode_guid, &pi_data.episode).into())
+                .unwrap();
+        }
+
+        let timestamp_param = format!(
+            "&ts={}",
+            pi_data.episode.datePublished
+        );
+        let episode_object = build_episode_note_object(
+            &pi_data.episode,
+            podcast_guid.parse::<u64>().unwrap(),
+            "Title".to_string(),
+            timestamp_param
+        ).unwrap();
+
+        let episode_data = Status {
+            at_context: vec!(
+                "https://www.w3.org/ns/activitystreams".to_string(),
+            ),
+            id: format!(
+                "{}/episodes?id={}&statusid={}",
+                ap_base_url(),
+                podcast_guid,
+                episode_guid
+            ).to_string(),
+            r#type: "Note".to_string(),
+            summary: None,
+            inReplyTo: None,
+            published: iso8601(pi_data.episode.datePublished),
+            url: None,
+            attributedTo: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
+            to: vec!(
+                "https://www.w3.org/ns/activitystreams#Public".to_string()
+            ),
+            cc: Some(vec!(
+                format!(
+                    "{}/followers?id={}",
+                    ap_base_url(),
+                    podcast_guid
+                ).to_string()
+            )),
+            sensitive: false,
+            conversation: format!(
+                "{}/contexts?id={}&statusid={}",
+                ap_base_url(),
+                podcast_guid,
+                episode_guid
+            ).to_string(),
+            content: episode_object.content,
+            attachment: Some(episode_object.attachment),
+            actor: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
+            tag: vec!(),
+            replies: ap_build_replies_collection(
+                podcast_guid.parse::<u64>().unwrap_or(0),
+                episode_guid,
+            ).ok(),
+            likes: ap_build_reaction_collection(
+                "likes",
+                podcast_guid.parse::<u64>().unwrap_or(0),
+                episode_guid,
+            ).ok(),
+            shares: ap_build_reaction_collection(
+                "shares",
+                podcast_guid.parse::<u64>().unwrap_or(0),
+                episode_guid,
+            ).ok(),
+        };
+
+        match serde_json::to_string_pretty(&episode_data) {
+            Ok(json_result) => {
+                episode_json = json_result;
+            }
+            Err(e) => {
+                println!("Response prep error: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(500).unwrap())
+                    .body("Response prep error.".to_string().into())
                     .unwrap();
             }
         }
     }
 
-    return hyper::Response::builder()
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("{}", episode_json).into())
-        .unwrap();
+        .header("Vary", "Accept")
+        .body(episode_json.to_string().into())
+        .unwrap()
 }
 
-pub async fn contexts(ctx: Context) -> Response {
+pub async fn notes(ctx: Context) -> Response {
+
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -1856,14 +2499,147 @@ pub async fn contexts(ctx: Context) -> Response {
     );
     //println!("Context: {:#?}", ctx);
 
-    return hyper::Response::builder()
+    //Make sure a podcast id was given
+    let podcast_guid: u64 = match params.get("id") {
+        Some(resource) => {
+            println!("  Id: {}\n", resource);
+            resource.parse::<u64>().unwrap_or(0)
+        }
+        None => {
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    //Get the note id, which will be a status
+    let note_id: u64 = match params.get("statusid") {
+        Some(resource) => {
+            println!("  Status Id: {}\n", resource);
+            resource.parse::<u64>().unwrap_or(0)
+        }
+        None => {
+            println!("Invalid status id.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No status id given.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    //##: Look up the note we authored
+    let note = match dbif::get_note_from_db(&AP_DATABASE_FILE.to_string(), podcast_guid, note_id) {
+        Ok(note_record) => {
+            note_record
+        }
+        Err(e) => {
+            println!("Note not found: [{}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body("Note not found.".to_string().into())
+                .unwrap();
+        }
+    };
+    let create_action_object = ap_build_note_create(&note);
+
+    //##: People get a web page, servers get the AS2 object
+    let resource = params.get("resource").map(|r| r.as_str()).unwrap_or("post");
+    if !accepts_activity_json(&ctx) {
+        let mut in_reply_to_link = "".to_string();
+        if let Some(in_reply_to) = &create_action_object.object.inReplyTo {
+            in_reply_to_link = ap_build_in_reply_to_html(in_reply_to);
+        }
+        return hyper::Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-type", "text/html")
+            .header("Vary", "Accept")
+            .body(
+                format!("<!DOCTYPE html>
+<html lang='en'>
+  <head>
+    <meta charset='utf-8' />
+    <title>Note from {}@{}</title>
+    <link rel='alternate' type='application/activity+json' href='{}' />
+  </head>
+  <body>
+    <p><a href='{}'>{}@{}</a> - {}</p>
+    {}
+    <div>{}</div>
+  </body>
+  </html>",
+                        podcast_guid,
+                        ap_host(),
+                        html_escape(&create_action_object.object.id),
+                        html_escape(&create_action_object.actor),
+                        podcast_guid,
+                        ap_host(),
+                        create_action_object.published,
+                        in_reply_to_link,
+                        create_action_object.object.content,
+                ).into()
+            )
+            .unwrap();
+    }
+
+    //##: Otherwise hand back either the activity or the note itself
+    let json_result = if resource == "activity" {
+        serde_json::to_string_pretty(&create_action_object)
+    } else {
+        let note_object = create_action_object.object;
+        let note_data = Status {
+            at_context: vec!(
+                "https://www.w3.org/ns/activitystreams".to_string(),
+            ),
+            id: note_object.id,
+            r#type: note_object.r#type,
+            summary: note_object.summary,
+            inReplyTo: note_object.inReplyTo,
+            published: note_object.published,
+            url: Some(note_object.url),
+            attributedTo: note_object.attributedTo,
+            to: note_object.to,
+            cc: note_object.cc,
+            sensitive: note_object.sensitive,
+            conversation: note_object.conversation,
+            content: note_object.content,
+            attachment: Some(note_object.attachment),
+            actor: create_action_object.actor,
+            tag: vec!(),
+            replies: None,
+            likes: None,
+            shares: None,
+        };
+        serde_json::to_string_pretty(&note_data)
+    };
+    let note_json = match json_result {
+        Ok(json) => {
+            json
+        }
+        Err(e) => {
+            println!("Response prep error: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Response prep error.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    hyper::Response::builder()
         .status(StatusCode::OK)
         .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("").into())
-        .unwrap();
+        .header("Vary", "Accept")
+        .body(note_json.to_string().into())
+        .unwrap()
 }
 
-pub async fn followers(ctx: Context) -> Response {
+pub async fn replies(ctx: Context) -> Response {
+
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
 
     //Determine HTTP action
     let http_action = ctx.req.method().to_string();
@@ -1875,307 +2651,500 @@ pub async fn followers(ctx: Context) -> Response {
     );
     //println!("Context: {:#?}", ctx);
 
-    return hyper::Response::builder()
-        .status(StatusCode::OK)
-        .header("Content-type", "application/activity+json; charset=utf-8")
-        .body(format!("").into())
-        .unwrap();
-}
-
+    //Make sure a podcast id was given
+    let podcast_guid = match params.get("id").and_then(|id| id.parse::<u64>().ok()) {
+        Some(pcid) => {
+            println!("  Id: {}\n", pcid);
+            pcid
+        }
+        None => {
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    };
 
-//API calls --------------------------------------------------------------------------------------------------
-pub async fn api_get_podcast(key: &str, secret: &str, query: &str) -> Result<String, Box<dyn Error>> {
-    println!("PI API Request: /podcasts/byfeedid");
-
-    let api_key = key;
-    let api_secret = secret;
-
-    //##: ======== Required values ========
-    //##: WARNING: don't publish these to public repositories or in public places!
-    //##: NOTE: values below are sample values, to get your own values go to https://api.podcastindex.org
-    let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
-
-    //##: Create the authorization token.
-    //##: The auth token is built by creating an sha1 hash of the key, secret and current time (as a string)
-    //##: concatenated together. The hash is a lowercase string.
-    let data4hash: String = format!("{}{}{}", api_key, api_secret, api_time);
-    //println!("Data to hash: [{}]", data4hash);
-    let mut hasher = Sha1::new();
-    hasher.update(data4hash);
-    let authorization_token = hasher.finalize();
-    let api_hash: String = format!("{:X}", authorization_token).to_lowercase();
-    //println!("Hash String: [{}]", api_hash);
-
-    //##: Set up the parameters and the api endpoint url to call and make sure all params are
-    //##: url encoded before sending.
-    let url: String = format!("https://api.podcastindex.org/api/1.0/podcasts/byfeedid?id={}", urlencoding::encode(query));
+    //Get an episode guid, which will be a status
+    let episode_guid = match params.get("statusid") {
+        Some(resource) => {
+            println!("  Status Id: {}\n", resource);
+            resource
+        }
+        None => {
+            println!("Invalid status id.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No status id given.".to_string().into())
+                .unwrap();
+        }
+    };
 
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
-    headers.insert("X-Auth-Date", header::HeaderValue::from_str(api_time.as_str()).unwrap());
-    headers.insert("X-Auth-Key", header::HeaderValue::from_str(api_key).unwrap());
-    headers.insert("Authorization", header::HeaderValue::from_str(api_hash.as_str()).unwrap());
-    let client = reqwest::Client::builder().default_headers(headers).build().unwrap();
-
-    //##: Send the request and display the results or the error
-    let res = client.get(url.as_str()).send();
-    match res.await {
-        Ok(res) => {
-            println!("  Response: [{}]", res.status());
-            return Ok(res.text().await.unwrap());
+    //Give back either the collection or the requested page of it
+    let page = params.get("page").and_then(|page| page.parse::<u64>().ok());
+    if page.is_some_and(|page| ap_collection_page_offset(page.max(1)).is_none()) {
+        return hyper::Response::builder()
+            .status(StatusCode::from_u16(400).unwrap())
+            .body("Page out of range.".to_string().into())
+            .unwrap();
+    }
+    let replies_result = match page {
+        Some(page) => {
+            ap_build_replies_page(podcast_guid, episode_guid, page.max(1)).map(|mut replies_page| {
+                replies_page.at_context = Some("https://www.w3.org/ns/activitystreams".to_string());
+                serde_json::to_string_pretty(&replies_page)
+            })
+        }
+        None => {
+            ap_build_replies_collection(podcast_guid, episode_guid).map(|mut replies_collection| {
+                replies_collection.at_context = Some("https://www.w3.org/ns/activitystreams".to_string());
+                serde_json::to_string_pretty(&replies_collection)
+            })
+        }
+    };
+    let replies_json = match replies_result {
+        Ok(Ok(json_result)) => {
+            json_result
+        }
+        Ok(Err(e)) => {
+            println!("Response prep error: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Response prep error.".to_string().into())
+                .unwrap();
         }
         Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error running SQL query: [{}]", e).into())));
+            eprintln!("Error getting replies: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Reply lookup error.".to_string().into())
+                .unwrap();
         }
-    }
+    };
+
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/activity+json; charset=utf-8")
+        .body(replies_json.to_string().into())
+        .unwrap()
 }
 
-pub async fn api_hub_rescan(key: &str, secret: &str, query: &str) -> Result<String, Box<dyn Error>> {
-    println!("PI HUB Request: /pubnotify?id={}", query);
+pub async fn likes(ctx: Context) -> Response {
+    return reactions(ctx, "likes").await;
+}
 
-    let api_key = key;
-    let api_secret = secret;
+pub async fn shares(ctx: Context) -> Response {
+    return reactions(ctx, "shares").await;
+}
 
-    //##: ======== Required values ========
-    //##: WARNING: don't publish these to public repositories or in public places!
-    //##: NOTE: values below are sample values, to get your own values go to https://api.podcastindex.org
-    let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
+async fn reactions(ctx: Context, kind: &str) -> Response {
 
-    //##: Create the authorization token.
-    //##: The auth token is built by creating an sha1 hash of the key, secret and current time (as a string)
-    //##: concatenated together. The hash is a lowercase string.
-    let data4hash: String = format!("{}{}{}", api_key, api_secret, api_time);
-    //println!("Data to hash: [{}]", data4hash);
-    let mut hasher = Sha1::new();
-    hasher.update(data4hash);
-    let authorization_token = hasher.finalize();
-    let api_hash: String = format!("{:X}", authorization_token).to_lowercase();
-    //println!("Hash String: [{}]", api_hash);
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
 
-    //##: Set up the parameters and the api endpoint url to call and make sure all params are
-    //##: url encoded before sending.
-    let url: String = format!("https://hub.podcastindex.org/pubnotify?id={}", urlencoding::encode(query));
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+    //println!("Context: {:#?}", ctx);
 
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
-    headers.insert("X-Auth-Date", header::HeaderValue::from_str(api_time.as_str()).unwrap());
-    headers.insert("X-Auth-Key", header::HeaderValue::from_str(api_key).unwrap());
-    headers.insert("Authorization", header::HeaderValue::from_str(api_hash.as_str()).unwrap());
-    let client = reqwest::Client::builder().default_headers(headers).build().unwrap();
-
-    //##: Send the request and display the results or the error
-    let res = client.get(url.as_str()).send();
-    match res.await {
-        Ok(res) => {
-            println!("  Response: [{}]", res.status());
-            return Ok(res.text().await.unwrap());
+    //Make sure a podcast id was given
+    let podcast_guid = match params.get("id").and_then(|id| id.parse::<u64>().ok()) {
+        Some(pcid) => {
+            println!("  Id: {}\n", pcid);
+            pcid
+        }
+        None => {
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    //Get an episode guid, which will be a status
+    let episode_guid = match params.get("statusid") {
+        Some(resource) => {
+            println!("  Status Id: {}\n", resource);
+            resource
+        }
+        None => {
+            println!("Invalid status id.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No status id given.".to_string().into())
+                .unwrap();
+        }
+    };
+
+    let reactions_json;
+    match ap_build_reaction_collection(kind, podcast_guid, episode_guid) {
+        Ok(mut reactions_collection) => {
+            reactions_collection.at_context = Some("https://www.w3.org/ns/activitystreams".to_string());
+            match serde_json::to_string_pretty(&reactions_collection) {
+                Ok(json_result) => {
+                    reactions_json = json_result;
+                }
+                Err(e) => {
+                    println!("Response prep error: [{:#?}].\n", e);
+                    return hyper::Response::builder()
+                        .status(StatusCode::from_u16(500).unwrap())
+                        .body("Response prep error.".to_string().into())
+                        .unwrap();
+                }
+            }
         }
         Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error running SQL query: [{}]", e).into())));
+            eprintln!("Error getting {}: [{:#?}].\n", kind, e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Reaction lookup error.".to_string().into())
+                .unwrap();
         }
     }
+
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/activity+json; charset=utf-8")
+        .body(reactions_json.to_string().into())
+        .unwrap()
 }
 
-pub async fn api_get_episodes(key: &str, secret: &str, query: &str) -> Result<String, Box<dyn Error>> {
-    println!("  PI API Request: /episodes/byfeedid");
+pub async fn contexts(ctx: Context) -> Response {
 
-    let api_key = key;
-    let api_secret = secret;
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
 
-    //##: ======== Required values ========
-    //##: WARNING: don't publish these to public repositories or in public places!
-    //##: NOTE: values below are sample values, to get your own values go to https://api.podcastindex.org
-    let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
+    );
+    //println!("Context: {:#?}", ctx);
 
-    //##: Create the authorization token.
-    //##: The auth token is built by creating an sha1 hash of the key, secret and current time (as a string)
-    //##: concatenated together. The hash is a lowercase string.
-    let data4hash: String = format!("{}{}{}", api_key, api_secret, api_time);
-    //println!("Data to hash: [{}]", data4hash);
-    let mut hasher = Sha1::new();
-    hasher.update(data4hash);
-    let authorization_token = hasher.finalize();
-    let api_hash: String = format!("{:X}", authorization_token).to_lowercase();
-    //println!("Hash String: [{}]", api_hash);
+    //Make sure a podcast id was given
+    let podcast_guid = match params.get("id").and_then(|id| id.parse::<u64>().ok()) {
+        Some(pcid) => {
+            println!("  Id: {}\n", pcid);
+            pcid
+        }
+        None => {
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    };
 
-    //##: Set up the parameters and the api endpoint url to call and make sure all params are
-    //##: url encoded before sending.
-    let url: String = format!("https://api.podcastindex.org/api/1.0/episodes/byfeedid?id={}", urlencoding::encode(query));
+    //Get an episode guid, which will be a status
+    let episode_guid = match params.get("statusid") {
+        Some(resource) => {
+            println!("  Status Id: {}\n", resource);
+            resource.clone()
+        }
+        None => {
+            println!("Invalid status id.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No status id given.".to_string().into())
+                .unwrap();
+        }
+    };
 
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
-    headers.insert("X-Auth-Date", header::HeaderValue::from_str(api_time.as_str()).unwrap());
-    headers.insert("X-Auth-Key", header::HeaderValue::from_str(api_key).unwrap());
-    headers.insert("Authorization", header::HeaderValue::from_str(api_hash.as_str()).unwrap());
-    let client = reqwest::Client::builder().default_headers(headers).build().unwrap();
-
-    //##: Send the request and display the results or the error
-    let res = client.get(url.as_str()).send();
-    match res.await {
-        Ok(res) => {
-            println!("  Response: [{}]", res.status());
-            return Ok(res.text().await.unwrap());
+    //##: Replies can reference the conversation by either the context url or the tag uri that was in the
+    //##: note we sent out.  The tag uri needs the episode publish date, so look the episode up
+    let context_id = format!(
+        "{}/contexts?id={}&statusid={}",
+        ap_base_url(),
+        podcast_guid,
+        episode_guid
+    );
+    let mut conversations = vec!(context_id.clone());
+    if episode_guid != "0" {
+        let api_response = ctx.pi_client.get_episode(&podcast_guid.to_string(), &episode_guid).await;
+        match api_response {
+            Ok(pi_data) => {
+                conversations.push(format!(
+                    "tag:{},{}:objectId={}:objectType=Conversation",
+                    ap_host(),
+                    iso8601(pi_data.episode.datePublished),
+                    pi_data.episode.guid
+                ));
+            }
+            Err(PIError::NotFound(e)) => {
+                println!("Episode not found: [{}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body("Episode not found.".to_string().into())
+                    .unwrap();
+            }
+            Err(e) => {
+                println!("Response prep error: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(501).unwrap())
+                    .body("Response prep error.".to_string().into())
+                    .unwrap();
+            }
+        }
+    }
+
+    //##: The episode note starts the conversation, followed by every reply we know of
+    let mut ordered_items = vec!(
+        format!(
+            "{}/episodes?id={}&statusid={}",
+            ap_base_url(),
+            podcast_guid,
+            episode_guid
+        )
+    );
+    match dbif::get_replies_from_db_by_context(
+        &AP_DATABASE_FILE.to_string(),
+        podcast_guid,
+        episode_guid.clone(),
+        conversations,
+    ) {
+        Ok(replies) => {
+            for reply in replies {
+                ordered_items.push(reply.objectid);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error getting replies: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Reply lookup error.".to_string().into())
+                .unwrap();
+        }
+    }
+
+    let context_data = OrderedCollection {
+        at_context: Some("https://www.w3.org/ns/activitystreams".to_string()),
+        id: context_id,
+        r#type: "OrderedCollection".to_string(),
+        totalItems: ordered_items.len() as u64,
+        first: None,
+        last: None,
+        orderedItems: Some(ordered_items),
+    };
+
+    let context_json = match serde_json::to_string_pretty(&context_data) {
+        Ok(json_result) => {
+            json_result
         }
         Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error running SQL query: [{}]", e).into())));
+            println!("Response prep error: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Response prep error.".to_string().into())
+                .unwrap();
         }
-    }
-}
-
-pub async fn api_get_episode(key: &str, secret: &str, query: &str, guid: &str) -> Result<String, Box<dyn Error>> {
-    println!("  PI API Request: /episodes/byguid");
+    };
 
-    let api_key = key;
-    let api_secret = secret;
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/activity+json; charset=utf-8")
+        .body(context_json.to_string().into())
+        .unwrap()
+}
 
-    //##: ======== Required values ========
-    //##: WARNING: don't publish these to public repositories or in public places!
-    //##: NOTE: values below are sample values, to get your own values go to https://api.podcastindex.org
-    let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
+pub async fn followers(ctx: Context) -> Response {
 
-    //##: Create the authorization token.
-    //##: The auth token is built by creating an sha1 hash of the key, secret and current time (as a string)
-    //##: concatenated together. The hash is a lowercase string.
-    let data4hash: String = format!("{}{}{}", api_key, api_secret, api_time);
-    //println!("Data to hash: [{}]", data4hash);
-    let mut hasher = Sha1::new();
-    hasher.update(data4hash);
-    let authorization_token = hasher.finalize();
-    let api_hash: String = format!("{:X}", authorization_token).to_lowercase();
-    //println!("Hash String: [{}]", api_hash);
+    //Get query parameters
+    let params: HashMap<String, String> = ctx.req.uri().query().map(|v| {
+        url::form_urlencoded::parse(v.as_bytes()).into_owned().collect()
+    }).unwrap_or_default();
 
-    //##: Set up the parameters and the api endpoint url to call and make sure all params are
-    //##: url encoded before sending.
-    let url: String = format!(
-        "https://api.podcastindex.org/api/1.0/episodes/byguid?guid={}&feedid={}",
-        urlencoding::encode(guid),
-        urlencoding::encode(query)
+    //Determine HTTP action
+    let http_action = ctx.req.method().to_string();
+    println!("\n\n----------");
+    println!("Request[{}]: {} from: {:#?}",
+             http_action,
+             ctx.req.uri(),
+             ctx.req.headers().get("user-agent")
     );
+    //println!("Context: {:#?}", ctx);
+
+    //Make sure a podcast id was given
+    let podcast_guid = match params.get("id").and_then(|id| id.parse::<u64>().ok()) {
+        Some(pcid) => {
+            println!("  Id: {}\n", pcid);
+            pcid
+        }
+        None => {
+            println!("Invalid resource.\n");
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(400).unwrap())
+                .body("No resource given.".to_string().into())
+                .unwrap();
+        }
+    };
 
-    println!("  PI API Request: [{}]", url);
+    //Which page, if any, was asked for
+    let mut page = 0;
+    match params.get("page") {
+        Some(page_param) => {
+            println!("  Got a page value: {}\n", page_param);
+            page = match page_param.as_str() {
+                "true" => 1,
+                _ => page_param.parse::<u64>().unwrap_or(1).max(1),
+            };
+        }
+        None => {
+            println!("  Non-paged request.");
+        }
+    }
 
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
-    headers.insert("X-Auth-Date", header::HeaderValue::from_str(api_time.as_str()).unwrap());
-    headers.insert("X-Auth-Key", header::HeaderValue::from_str(api_key).unwrap());
-    headers.insert("Authorization", header::HeaderValue::from_str(api_hash.as_str()).unwrap());
-    let client = reqwest::Client::builder().default_headers(headers).build().unwrap();
-
-    //##: Send the request and display the results or the error
-    let res = client.get(url.as_str()).send();
-    match res.await {
-        Ok(res) => {
-            println!("  Response: [{}]", res.status());
-            return Ok(res.text().await.unwrap());
+    let follower_count = match dbif::get_follower_count_from_db(&AP_DATABASE_FILE.to_string(), podcast_guid) {
+        Ok(count) => {
+            count
         }
         Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error running SQL query: [{}]", e).into())));
+            eprintln!("Error getting follower count: [{:#?}].\n", e);
+            return hyper::Response::builder()
+                .status(StatusCode::from_u16(500).unwrap())
+                .body("Follower lookup error.".to_string().into())
+                .unwrap();
         }
-    }
-}
+    };
 
-pub fn api_block_get_episodes(key: &str, secret: &str, query: &str) -> Result<String, Box<dyn Error>> {
-    //println!("  PI API Request: /episodes/byfeedid");
+    let hide_followers = AP_HIDE_FOLLOWERS.load(Ordering::Relaxed);
+    let collection_id = format!("{}/followers?id={}", ap_base_url(), podcast_guid);
 
-    let api_key = key;
-    let api_secret = secret;
+    //If no page was given, just give the collection summary.  When the member list is hidden, only
+    //the count is given out
+    let followers_json;
+    if page == 0 {
+        let mut followers_data = OrderedCollection {
+            at_context: Some("https://www.w3.org/ns/activitystreams".to_string()),
+            id: collection_id.clone(),
+            r#type: "OrderedCollection".to_string(),
+            totalItems: follower_count,
+            first: None,
+            last: None,
+            orderedItems: None,
+        };
+        if !hide_followers {
+            followers_data.first = Some(format!("{}&page=1", collection_id));
+        }
 
-    //##: ======== Required values ========
-    //##: WARNING: don't publish these to public repositories or in public places!
-    //##: NOTE: values below are sample values, to get your own values go to https://api.podcastindex.org
-    let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
+        match serde_json::to_string_pretty(&followers_data) {
+            Ok(json_result) => {
+                followers_json = json_result;
+            }
+            Err(e) => {
+                println!("Response prep error: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(500).unwrap())
+                    .body("Response prep error.".to_string().into())
+                    .unwrap();
+            }
+        }
 
-    //##: Create the authorization token.
-    //##: The auth token is built by creating an sha1 hash of the key, secret and current time (as a string)
-    //##: concatenated together. The hash is a lowercase string.
-    let data4hash: String = format!("{}{}{}", api_key, api_secret, api_time);
-    //println!("Data to hash: [{}]", data4hash);
-    let mut hasher = Sha1::new();
-    hasher.update(data4hash);
-    let authorization_token = hasher.finalize();
-    let api_hash: String = format!("{:X}", authorization_token).to_lowercase();
-    //println!("Hash String: [{}]", api_hash);
+    //Otherwise give back a page of follower actor ids
+    } else {
+        if hide_followers {
+            return hyper::Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body("Follower list is hidden.".to_string().into())
+                .unwrap();
+        }
 
-    //##: Set up the parameters and the api endpoint url to call and make sure all params are
-    //##: url encoded before sending.
-    let url: String = format!("https://api.podcastindex.org/api/1.0/episodes/byfeedid?id={}", urlencoding::encode(query));
+        let offset = match ap_collection_page_offset(page) {
+            Some(page_offset) => {
+                page_offset
+            }
+            None => {
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(400).unwrap())
+                    .body("Page out of range.".to_string().into())
+                    .unwrap();
+            }
+        };
+        let followers_page = match dbif::get_followers_page_from_db(
+            &AP_DATABASE_FILE.to_string(),
+            podcast_guid,
+            offset,
+            AP_COLLECTION_PAGE_SIZE,
+        ) {
+            Ok(followers) => {
+                followers
+            }
+            Err(e) => {
+                eprintln!("Error getting followers: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(500).unwrap())
+                    .body("Follower lookup error.".to_string().into())
+                    .unwrap();
+            }
+        };
 
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
-    headers.insert("X-Auth-Date", header::HeaderValue::from_str(api_time.as_str()).unwrap());
-    headers.insert("X-Auth-Key", header::HeaderValue::from_str(api_key).unwrap());
-    headers.insert("Authorization", header::HeaderValue::from_str(api_hash.as_str()).unwrap());
-    let client = reqwest::blocking::Client::builder().default_headers(headers).build().unwrap();
-
-    //##: Send the request and display the results or the error
-    let res = client.get(url.as_str()).send();
-    match res {
-        Ok(res) => {
-            //println!("  Response: [{}]", res.status());
-            return Ok(res.text().unwrap());
+        let mut followers_data = OrderedCollectionPage {
+            at_context: "https://www.w3.org/ns/activitystreams".to_string(),
+            id: format!("{}&page={}", collection_id, page),
+            r#type: "OrderedCollectionPage".to_string(),
+            totalItems: follower_count,
+            partOf: collection_id.clone(),
+            next: None,
+            prev: None,
+            orderedItems: followers_page.into_iter().map(|follower| follower.actor).collect::<Vec<String>>(),
+        };
+        if offset.saturating_add(AP_COLLECTION_PAGE_SIZE) < follower_count {
+            followers_data.next = Some(format!("{}&page={}", collection_id, page + 1));
         }
-        Err(e) => {
-            //eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error running SQL query: [{}]", e).into())));
+        if page > 1 {
+            followers_data.prev = Some(format!("{}&page={}", collection_id, page - 1));
         }
-    }
-}
 
-pub fn api_block_get_live_items(key: &str, secret: &str, query: &str) -> Result<String, Box<dyn Error>> {
-    println!("  PI API Request: /live/byfeedurl");
+        match serde_json::to_string_pretty(&followers_data) {
+            Ok(json_result) => {
+                followers_json = json_result;
+            }
+            Err(e) => {
+                println!("Response prep error: [{:#?}].\n", e);
+                return hyper::Response::builder()
+                    .status(StatusCode::from_u16(500).unwrap())
+                    .body("Response prep error.".to_string().into())
+                    .unwrap();
+            }
+        }
+    }
 
-    let api_key = key;
-    let api_secret = secret;
+    hyper::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-type", "application/activity+json; charset=utf-8")
+        .body(followers_json.to_string().into())
+        .unwrap()
+}
 
-    //##: ======== Required values ========
-    //##: WARNING: don't publish these to public repositories or in public places!
-    //##: NOTE: values below are sample values, to get your own values go to https://api.podcastindex.org
-    let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
+//##: Where a collection page starts.  None when the page is too far out for the offset to fit in a query
+fn ap_collection_page_offset(page: u64) -> Option<u64> {
+    page
+        .checked_sub(1)
+        .and_then(|page_index| page_index.checked_mul(AP_COLLECTION_PAGE_SIZE))
+        .filter(|offset| *offset <= i64::MAX as u64)
+}
 
-    //##: Create the authorization token.
-    //##: The auth token is built by creating an sha1 hash of the key, secret and current time (as a string)
-    //##: concatenated together. The hash is a lowercase string.
-    let data4hash: String = format!("{}{}{}", api_key, api_secret, api_time);
-    //println!("Data to hash: [{}]", data4hash);
-    let mut hasher = Sha1::new();
-    hasher.update(data4hash);
-    let authorization_token = hasher.finalize();
-    let api_hash: String = format!("{:X}", authorization_token).to_lowercase();
-    //println!("Hash String: [{}]", api_hash);
 
-    //##: Set up the parameters and the api endpoint url to call and make sure all params are
-    //##: url encoded before sending.
-    let url: String = format!("https://api.podcastindex.org/api/1.0/live/byfeedurl?url={}", urlencoding::encode(query));
+//API calls --------------------------------------------------------------------------------------------------
+pub async fn api_find_podcast_id_by_slug(pi_client: &dyn PodcastIndexClient, slug: &str) -> PIResult<Option<u64>> {
+    //##: There's no slug lookup in the index, so search by title and take the one that slugs the same
+    let search_result = pi_client.search_podcasts_by_title(&slug.replace('-', " ")).await?;
 
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
-    headers.insert("X-Auth-Date", header::HeaderValue::from_str(api_time.as_str()).unwrap());
-    headers.insert("X-Auth-Key", header::HeaderValue::from_str(api_key).unwrap());
-    headers.insert("Authorization", header::HeaderValue::from_str(api_hash.as_str()).unwrap());
-    let client = reqwest::blocking::Client::builder().default_headers(headers).build().unwrap();
-
-    //##: Send the request and display the results or the error
-    let res = client.get(url.as_str()).send();
-    match res {
-        Ok(res) => {
-            println!("  Response: [{}]", res.status());
-            return Ok(res.text().unwrap());
-        }
-        Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error making pi api request: [{}]", e).into())));
-        }
-    }
+    Ok(search_result.feeds.iter().find(|feed| slugify(&feed.title) == slug).map(|feed| feed.id))
 }
 
 
@@ -2188,30 +3157,30 @@ fn ap_build_actor_object(podcast_data: PIPodcast, actor_keys: ActorKeys) -> Resu
             "https://www.w3.org/ns/activitystreams".to_string(),
             "https://w3id.org/security/v1".to_string(),
         ),
-        id: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+        id: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
         r#type: "Service".to_string(),
         discoverable: Some(true),
         preferredUsername: Some(podcast_guid.to_string()),
         name: Some(format!("{:.48}", podcast_data.feed.title).to_string()),
-        inbox: format!("https://ap.podcastindex.org/inbox?id={}", podcast_guid).to_string(),
-        outbox: format!("https://ap.podcastindex.org/outbox?id={}", podcast_guid).to_string(),
-        featured: Some(format!("https://ap.podcastindex.org/featured?id={}", podcast_guid).to_string()),
-        followers: Some(format!("https://ap.podcastindex.org/followers?id={}", podcast_guid).to_string()),
-        following: Some(format!("https://ap.podcastindex.org/following?id={}", podcast_guid).to_string()),
+        inbox: format!("{}/inbox?id={}", ap_base_url(), podcast_guid).to_string(),
+        outbox: format!("{}/outbox?id={}", ap_base_url(), podcast_guid).to_string(),
+        featured: Some(format!("{}/featured?id={}", ap_base_url(), podcast_guid).to_string()),
+        followers: Some(format!("{}/followers?id={}", ap_base_url(), podcast_guid).to_string()),
+        following: Some(format!("{}/following?id={}", ap_base_url(), podcast_guid).to_string()),
         icon: Some(Icon {
             r#type: "Image".to_string(),
             mediaType: None,
-            url: format!("{}", podcast_data.feed.image).to_string(),
+            url: podcast_data.feed.image.to_string().to_string(),
         }),
         summary: Some(format!("{:.96}", podcast_data.feed.description)),
         attachment: None,
         publicKey: PublicKey {
-            id: format!("https://ap.podcastindex.org/podcasts?id={}#main-key", podcast_guid).to_string(),
-            owner: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+            id: format!("{}/podcasts?id={}#main-key", ap_base_url(), podcast_guid).to_string(),
+            owner: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
             publicKeyPem: actor_keys.pem_public_key,
         },
         endpoints: Some(Endpoints {
-            sharedInbox: "https://ap.podcastindex.org/inbox?id=0".to_string(),
+            sharedInbox: format!("{}/inbox?id=0", ap_base_url()).to_string(),
         }),
         url: Some(format!("https://podcastindex.org/podcast/{}", podcast_guid).to_string()),
         manuallyApprovesFollowers: Some(false),
@@ -2255,42 +3224,352 @@ fn ap_build_actor_object(podcast_data: PIPodcast, actor_keys: ActorKeys) -> Resu
         Attachment {
             name: Some("Podcast Guid".to_string()),
             r#type: "PropertyValue".to_string(),
-            value: Some(format!(
-                "{}",
-                podcast_data.feed.podcastGuid,
-            ).to_string()),
+            value: Some(podcast_data.feed.podcastGuid.to_string().to_string()),
         },
     );
     //##: Funding tag present?
-    match podcast_data.feed.funding {
-        Some(funding) => {
-            let funding_url = funding.url.unwrap_or("".to_string());
-            let funding_message = funding.message.unwrap_or(funding_url.clone());
-
-            attachments.push(
-                Attachment {
-                    name: Some("Funding".to_string()),
-                    r#type: "PropertyValue".to_string(),
-                    value: Some(format!(
-                        "<a href='{}' rel='ugc'>{}</a>",
-                        funding_url,
-                        funding_message
-                    ).to_string()),
-                }
-            );
-        }
-        None => {}
+    if let Some(funding) = podcast_data.feed.funding {
+        let funding_url = funding.url.unwrap_or("".to_string());
+        let funding_message = funding.message.unwrap_or(funding_url.clone());
+
+        attachments.push(
+            Attachment {
+                name: Some("Funding".to_string()),
+                r#type: "PropertyValue".to_string(),
+                value: Some(format!(
+                    "<a href='{}' rel='ugc'>{}</a>",
+                    funding_url,
+                    funding_message
+                ).to_string()),
+            }
+        );
     }
     actor.attachment = Some(attachments);
 
-    return Ok(actor);
+    Ok(actor)
+}
+
+//##: Build an outbox activity for an episode.  Podcast actors Create their episodes, the instance actor
+//##: Announces them
+fn ap_build_outbox_item(episode: &PIItem, activity_type: &str) -> Item {
+    let podcast_guid = episode.feedId;
+    let mut activity_id = format!(
+        "{}/episodes?id={}&statusid={}&resource=activity",
+        ap_base_url(),
+        podcast_guid,
+        episode.guid
+    ).to_string();
+    let mut actor = format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string();
+    if activity_type == "Announce" {
+        activity_id = format!(
+            "{}/episodes?id={}&statusid={}&resource=announce",
+            ap_base_url(),
+            podcast_guid,
+            episode.guid
+        ).to_string();
+        actor = format!("{}/podcasts", ap_base_url()).to_string();
+    }
+
+    Item {
+        id: activity_id,
+        r#type: activity_type.to_string(),
+        actor,
+        published: iso8601(episode.datePublished),
+        directMessage: false,
+        to: vec!(
+            "https://www.w3.org/ns/activitystreams#Public".to_string()
+        ),
+        object: Object {
+            id: format!(
+                "{}/episodes?id={}&statusid={}&resource=post",
+                ap_base_url(),
+                podcast_guid,
+                episode.guid
+            ).to_string(),
+            r#type: "Note".to_string(),
+            summary: None,
+            inReplyTo: None,
+            published: iso8601(episode.datePublished),
+            url: format!(
+                "{}/episodes?id={}&statusid={}&resource=public",
+                ap_base_url(),
+                podcast_guid,
+                episode.guid
+            ).to_string(),
+            attributedTo: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
+            to: vec!(
+                "https://www.w3.org/ns/activitystreams#Public".to_string()
+            ),
+            cc: None,
+            sensitive: false,
+            conversation: format!(
+                "tag:{},{}:objectId={}:objectType=Conversation",
+                ap_host(),
+                iso8601(episode.datePublished),
+                episode.guid
+            ).to_string(),
+            content: format!(
+                "<p>{:.128}</p><p>{:.128}</p><p>Listen: {}</p>",
+                episode.title,
+                episode.description,
+                episode.enclosureUrl
+            ),
+            attachment: vec!(),
+        },
+    }
+}
+
+//##: The note being replied to comes from whoever sent us the request, so it's only linked when it's a web url
+fn ap_build_in_reply_to_html(in_reply_to: &str) -> String {
+    if !is_web_url(in_reply_to) {
+        return format!("<p>In reply to {}</p>", html_escape(in_reply_to));
+    }
+
+    format!(
+        "<p>In reply to <a href='{}'>{}</a></p>",
+        html_escape_url(in_reply_to),
+        html_escape(in_reply_to),
+    )
+}
+
+fn ap_build_episode_html(episode: &PIItem, podcast_guid: &str) -> String {
+    let episode_url = format!(
+        "{}/episodes?id={}&statusid={}&resource=public",
+        ap_base_url(),
+        podcast_guid,
+        urlencoding::encode(&episode.guid)
+    );
+
+    format!(
+        "<article>
+      <h3><a href='{}'>{}</a></h3>
+      <p>{}</p>
+      <audio controls preload='none' src='{}'></audio>
+    </article>
+    ",
+        html_escape(&episode_url),
+        html_escape(&episode.title),
+        html_escape(&episode.datePublishedPretty),
+        html_escape_url(&episode.enclosureUrl),
+    )
+}
+
+fn ap_build_episode_page_html(podcast_guid: &str, episode_guid: &str, episode: &PIItem) -> String {
+    let mut body_html = format!(
+        "<h2>{}</h2>
+    <p>{}</p>
+    <audio controls preload='none' src='{}'></audio>
+    <p style='white-space: pre-line'>{}</p>
+    ",
+        html_escape(&episode.title),
+        html_escape(&episode.datePublishedPretty),
+        html_escape_url(&episode.enclosureUrl),
+        html_escape(&html_strip_tags(&episode.description)),
+    );
+
+    //##: Show the replies we've been sent about this episode
+    let replies = dbif::get_replies_from_db_by_episode(
+        &AP_DATABASE_FILE.to_string(),
+        podcast_guid.parse::<u64>().unwrap_or(0),
+        episode_guid.to_string(),
+    ).unwrap_or(vec!());
+    if !replies.is_empty() {
+        body_html.push_str("<h3>Replies</h3>\n");
+    }
+    for reply in replies.iter() {
+        body_html.push_str(format!(
+            "<article>
+      <p><a href='{}'>{}</a> - {}</p>
+      <p>{}</p>
+    </article>
+    ",
+            html_escape_url(&reply.objectid),
+            html_escape(&reply.attributedto),
+            html_escape(&reply.published),
+            html_escape(&html_strip_tags(&reply.content)),
+        ).as_str());
+    }
+
+    ap_build_status_page_html(podcast_guid, &episode.title, &body_html)
+}
+
+fn ap_build_status_page_html(podcast_guid: &str, title: &str, body_html: &str) -> String {
+    format!("<!DOCTYPE html>
+<html lang='en'>
+  <head>
+    <meta charset='utf-8' />
+    <title>{}</title>
+  </head>
+  <body>
+    <p><a href='{}/profiles?id={}'>{}@{}</a></p>
+    {}
+  </body>
+  </html>",
+                   html_escape(title),
+                   ap_base_url(),
+                   html_escape(podcast_guid),
+                   html_escape(podcast_guid),
+                   ap_host(),
+                   body_html,
+    )
+}
+
+//##: Build the likes or shares collection for an episode status.  Like Mastodon, only the count is given out
+fn ap_build_reaction_collection(kind: &str, podcast_guid: u64, episode_guid: &str) -> Result<OrderedCollection, Box<dyn Error>> {
+    let reaction_count = match kind {
+        "likes" => {
+            dbif::get_like_count_from_db_by_episode(
+                &AP_DATABASE_FILE.to_string(),
+                podcast_guid,
+                episode_guid.to_string(),
+            )?
+        }
+        "shares" => {
+            dbif::get_share_count_from_db_by_episode(
+                &AP_DATABASE_FILE.to_string(),
+                podcast_guid,
+                episode_guid.to_string(),
+            )?
+        }
+        _ => {
+            return Err(Box::new(HydraError(format!("Unknown reaction collection: [{}]", kind))));
+        }
+    };
+
+    Ok(OrderedCollection {
+        at_context: None,
+        id: format!(
+            "{}/{}?id={}&statusid={}",
+            ap_base_url(),
+            kind,
+            podcast_guid,
+            urlencoding::encode(episode_guid)
+        ),
+        r#type: "Collection".to_string(),
+        totalItems: reaction_count,
+        first: None,
+        last: None,
+        orderedItems: None,
+    })
+}
+
+//##: Build the replies collection for an episode status, with the first page of replies embedded
+fn ap_build_replies_collection(podcast_guid: u64, episode_guid: &str) -> Result<ReplyCollection, Box<dyn Error>> {
+    let first_page = ap_build_replies_page(podcast_guid, episode_guid, 1)?;
+    let reply_count = dbif::get_reply_count_from_db_by_episode(
+        &AP_DATABASE_FILE.to_string(),
+        podcast_guid,
+        episode_guid.to_string(),
+    )?;
+
+    Ok(ReplyCollection {
+        at_context: None,
+        id: first_page.partOf.clone(),
+        r#type: Some("Collection".to_string()),
+        totalItems: Some(reply_count),
+        first: Some(first_page),
+    })
+}
+
+fn ap_build_replies_page(podcast_guid: u64, episode_guid: &str, page: u64) -> Result<ReplyCollectionPage, Box<dyn Error>> {
+    let collection_id = format!(
+        "{}/replies?id={}&statusid={}",
+        ap_base_url(),
+        podcast_guid,
+        urlencoding::encode(episode_guid)
+    );
+
+    let reply_count = dbif::get_reply_count_from_db_by_episode(
+        &AP_DATABASE_FILE.to_string(),
+        podcast_guid,
+        episode_guid.to_string(),
+    )?;
+    let offset = ap_collection_page_offset(page)
+        .ok_or_else(|| HydraError(format!("Replies page out of range: [{}]", page)))?;
+    let replies = dbif::get_replies_page_from_db_by_episode(
+        &AP_DATABASE_FILE.to_string(),
+        podcast_guid,
+        episode_guid.to_string(),
+        offset,
+        AP_COLLECTION_PAGE_SIZE,
+    )?;
+
+    let mut replies_page = ReplyCollectionPage {
+        at_context: None,
+        id: Some(format!("{}&page={}", collection_id, page)),
+        r#type: Some("CollectionPage".to_string()),
+        next: None,
+        partOf: Some(collection_id.clone()),
+        items: Some(replies.into_iter().map(|reply| reply.objectid).collect()),
+    };
+    if offset.saturating_add(AP_COLLECTION_PAGE_SIZE) < reply_count {
+        replies_page.next = Some(format!("{}&page={}", collection_id, page + 1));
+    }
+
+    Ok(replies_page)
+}
+
+fn ap_build_note_create(note: &NoteRecord) -> Create {
+    let mut in_reply_to = None;
+    if !note.in_reply_to.is_empty() {
+        in_reply_to = Some(note.in_reply_to.clone());
+    }
+
+    Create {
+        at_context: "https://www.w3.org/ns/activitystreams".to_string(),
+        id: format!(
+            "{}/notes?id={}&statusid={}&resource=activity",
+            ap_base_url(),
+            note.pcid,
+            note.id
+        ).to_string(),
+        r#type: "Create".to_string(),
+        actor: format!("{}/podcasts?id={}", ap_base_url(), note.pcid).to_string(),
+        published: iso8601(note.published),
+        to: vec!(
+            "https://www.w3.org/ns/activitystreams#Public".to_string()
+        ),
+        cc: None,
+        object: Object {
+            id: format!(
+                "{}/notes?id={}&statusid={}&resource=post",
+                ap_base_url(),
+                note.pcid,
+                note.id
+            ).to_string(),
+            r#type: "Note".to_string(),
+            summary: None,
+            inReplyTo: in_reply_to,
+            published: iso8601(note.published),
+            url: format!(
+                "{}/notes?id={}&statusid={}&resource=public",
+                ap_base_url(),
+                note.pcid,
+                note.id
+            ).to_string(),
+            attributedTo: format!("{}/podcasts?id={}", ap_base_url(), note.pcid).to_string(),
+            to: vec!(
+                "https://www.w3.org/ns/activitystreams#Public".to_string()
+            ),
+            cc: None,
+            sensitive: false,
+            conversation: format!(
+                "tag:{},{}:objectId={}:objectType=Conversation",
+                ap_host(),
+                iso8601(note.published),
+                note.id
+            ).to_string(),
+            content: note.content.clone(),
+            attachment: vec!(),
+        },
+    }
 }
 
 fn ap_build_follow_accept(follow_request: InboxRequestWithObject, podcast_guid: u64) -> Result<InboxRequestAccept, Box<dyn Error>> {
-    return Ok(
+    Ok(
         InboxRequestAccept {
             at_context: "https://www.w3.org/ns/activitystreams".to_string(),
-            id: format!("https://ap.podcastindex.org/podcasts?id={}&context=accept", podcast_guid).to_string(),
+            id: format!("{}/podcasts?id={}&context=accept", ap_base_url(), podcast_guid).to_string(),
             r#type: "Accept".to_string(),
             actor: follow_request.object.id.clone(),
             object: InboxRequest {
@@ -2300,23 +3579,37 @@ fn ap_build_follow_accept(follow_request: InboxRequestWithObject, podcast_guid:
                 object: follow_request.object.id.clone(),
             },
         }
-    );
+    )
+}
+
+//##: Keys of an actor that already exists, falling back to the instance actor's
+fn ap_get_signing_keys(podcast_guid: u64) -> Result<(u64, ActorKeys), Box<dyn Error>> {
+    for signer_guid in [podcast_guid, 0] {
+        if let Ok(actor_record) = dbif::get_actor_from_db(&AP_DATABASE_FILE.to_string(), signer_guid) {
+            return Ok((signer_guid, ActorKeys {
+                pem_private_key: actor_record.pem_private_key,
+                pem_public_key: actor_record.pem_public_key,
+            }));
+        }
+    }
+
+    Err(Box::new(HydraError(format!("No keys to sign with for: [{}]", podcast_guid))))
+}
+
+//##: Make sure the instance actor has its keys before any requests come in
+pub fn ap_init_instance_actor() -> Result<(), Box<dyn Error>> {
+    ap_get_actor_keys(0)?;
+    Ok(())
 }
 
 fn ap_get_actor_keys(podcast_guid: u64) -> Result<ActorKeys, Box<dyn Error>> {
     println!("  Getting actor keys for: [{}]", podcast_guid);
 
-    let actor_keys;
-    let pem_pub_key;
-    let pem_priv_key;
-    match dbif::get_actor_from_db(&AP_DATABASE_FILE.to_string(), podcast_guid) {
-        Ok(actor_record) => {
-            pem_pub_key = actor_record.pem_public_key;
-            pem_priv_key = actor_record.pem_private_key;
-
-            actor_keys = ActorKeys {
-                pem_private_key: pem_priv_key.clone(),
-                pem_public_key: pem_pub_key.clone(),
+    let actor_keys = match dbif::get_actor_from_db(&AP_DATABASE_FILE.to_string(), podcast_guid) {
+        Ok(actor_record) => {
+            ActorKeys {
+                pem_private_key: actor_record.pem_private_key,
+                pem_public_key: actor_record.pem_public_key,
             }
         }
         Err(e) => {
@@ -2331,23 +3624,23 @@ fn ap_get_actor_keys(podcast_guid: u64) -> Result<ActorKeys, Box<dyn Error>> {
                 priv_key = RsaPrivateKey::new(&mut rng, bits).expect("failed to generate key");
                 pub_key = RsaPublicKey::from(&priv_key);
             }
-            match pkcs1::EncodeRsaPrivateKey::to_pkcs1_pem(&priv_key, pkcs1::LineEnding::LF) {
+            let pem_priv_key = match pkcs1::EncodeRsaPrivateKey::to_pkcs1_pem(&priv_key, pkcs1::LineEnding::LF) {
                 Ok(pem_encoded_privkey) => {
-                    pem_priv_key = pem_encoded_privkey.to_string();
+                    pem_encoded_privkey.to_string()
                 }
                 Err(e) => {
-                    return Err(Box::new(HydraError(format!("Error encoding private key: [{}]", e).into())));
+                    return Err(Box::new(HydraError(format!("Error encoding private key: [{}]", e))));
                 }
-            }
+            };
             println!("Private key: {:.40}", pem_priv_key);
-            match pkcs1::EncodeRsaPublicKey::to_pkcs1_pem(&pub_key, pkcs1::LineEnding::LF) {
+            let pem_pub_key = match pkcs1::EncodeRsaPublicKey::to_pkcs1_pem(&pub_key, pkcs1::LineEnding::LF) {
                 Ok(pem_encoded_pubkey) => {
-                    pem_pub_key = pem_encoded_pubkey.to_string();
+                    pem_encoded_pubkey.to_string()
                 }
                 Err(e) => {
-                    return Err(Box::new(HydraError(format!("Error encoding public key: [{}]", e).into())));
+                    return Err(Box::new(HydraError(format!("Error encoding public key: [{}]", e))));
                 }
-            }
+            };
             println!("Public key: {:.40}", pem_pub_key);
 
             let _ = dbif::add_actor_to_db(&AP_DATABASE_FILE.to_string(), ActorRecord {
@@ -2356,261 +3649,82 @@ fn ap_get_actor_keys(podcast_guid: u64) -> Result<ActorKeys, Box<dyn Error>> {
                 pem_private_key: pem_priv_key.clone(),
                 pem_public_key: pem_pub_key.clone(),
                 last_episode_guid: "".to_string(),
+                last_episode_published: 0,
+                feed_url: "".to_string(),
             });
             println!("Saved actor to DB");
 
-            actor_keys = ActorKeys {
-                pem_private_key: pem_priv_key.clone(),
-                pem_public_key: pem_pub_key.clone(),
+            ActorKeys {
+                pem_private_key: pem_priv_key,
+                pem_public_key: pem_pub_key,
             }
         }
-    }
+    };
 
-    return Ok(actor_keys);
+    Ok(actor_keys)
 }
 
-pub fn ap_send_follow_accept(podcast_guid: u64, inbox_accept: InboxRequestAccept, inbox_url: String) -> Result<String, Box<dyn Error>> {
+pub fn ap_send_follow_accept(podcast_guid: u64, inbox_accept: InboxRequestAccept, inbox_url: String) -> Result<u64, Box<dyn Error>> {
     println!("  AP Accepting Follow request from: {}", inbox_accept.object.actor);
 
-    //##: Get actor keys for guid
-    let actor_keys = ap_get_actor_keys(podcast_guid).unwrap();
-
-    //##: Decode the private key for the podcast actor
-    let private_key;
-    match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
-        Ok(pem_decoded_privkey) => {
-            private_key = pem_decoded_privkey;
-        }
-        Err(e) => {
-            eprintln!("Error decoding private key: [{}] for actor: [{}]", e, podcast_guid);
-            return Err(Box::new(HydraError(format!("Error decoding private key: [{}]", e).into())));
-        }
-    }
-
     //##: Construct the follow "accept" POST body to send
-    let post_body;
-    match serde_json::to_string_pretty(&inbox_accept) {
+    let post_body = match serde_json::to_string_pretty(&inbox_accept) {
         Ok(json_result) => {
-            post_body = json_result;
+            json_result
         }
         Err(e) => {
             eprintln!("Error building post body: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error building post body: [{}]", e).into())));
+            return Err(Box::new(HydraError(format!("Error building post body: [{}]", e))));
         }
-    }
+    };
     println!("  POST BODY: {}", post_body);
 
-    let key_id = format!("https://ap.podcastindex.org/podcasts?id={}#main-key", podcast_guid);
-    let http_signature_headers;
-    match http_signature::create_http_signature(
-        http::Method::POST,
-        &inbox_url,
-        &post_body.clone(),
-        &private_key,
-        &key_id,
-    ) {
-        Ok(sig_headers) => {
-            http_signature_headers = sig_headers;
-        }
-        Err(e) => {
-            eprintln!("Could not build http signature headers: [{}]", e);
-            return Err(Box::new(HydraError(format!("Could not build http signature headers: [{}]", e).into())));
-        }
-    }
-
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static("Podcast Index AP/v0.1.2a"));
-    headers.insert("Accept", header::HeaderValue::from_static("application/activity+json"));
-    headers.insert("Content-type", header::HeaderValue::from_static("application/activity+json"));
-    headers.insert("date", header::HeaderValue::from_str(&http_signature_headers.date).unwrap());
-    headers.insert("host", header::HeaderValue::from_str(&http_signature_headers.host).unwrap());
-    headers.insert("digest", header::HeaderValue::from_str(&http_signature_headers.digest.unwrap()).unwrap());
-    headers.insert("signature", header::HeaderValue::from_str(&http_signature_headers.signature).unwrap());
-    let client = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()
-        .unwrap();
-
-    //##: Send the Accept request
-    println!("  ACCEPT SENT: [{}]", inbox_url.as_str());
-    match client
-        .post(inbox_url.as_str())
-        .body(post_body)
-        .send()
-    {
-        Ok(res) => {
-            println!("  Response: [{:#?}]", res);
-            if res.status() >= StatusCode::from_u16(200)? && res.status() <= StatusCode::from_u16(299)? {
-                return Ok("".to_string());
-            } else {
-                let res_body = res.text().unwrap();
-                eprintln!("  Body: [{:#?}]", res_body);
-                return Err(Box::new(HydraError(format!("Accepting the follow request failed.").into())));
-            }
-        }
-        Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error sending follow accept request: [{}]", e).into())));
-        }
-    }
+    //##: Queue it up for the delivery worker, which signs and sends it
+    println!("  ACCEPT QUEUED: [{}|{}]", podcast_guid, inbox_url.as_str());
+    ap_queue_delivery(podcast_guid, inbox_url, post_body)
 }
 
-pub fn ap_block_send_note(podcast_guid: u64, inbox_url: String, note: String, in_reply_to: Option<String>) -> Result<String, Box<dyn Error>> {
-    println!("  AP Sending create episode note from actor: {}", podcast_guid);
-
-    //##: Get actor keys for guid
-    let actor_keys = ap_get_actor_keys(podcast_guid).unwrap();
-
-    //##: Decode the private key for the podcast actor
-    let private_key;
-    match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
-        Ok(pem_decoded_privkey) => {
-            private_key = pem_decoded_privkey;
-        }
-        Err(e) => {
-            return Err(Box::new(HydraError(format!("Error decoding private key: [{}]", e).into())));
-        }
-    }
+pub fn ap_send_note(podcast_guid: u64, inbox_url: String, note: String, in_reply_to: Option<String>) -> Result<u64, Box<dyn Error>> {
+    println!("  AP Sending create note from actor: {}", podcast_guid);
 
-    //##: Construct the note object to send
-    let mut create_action_object = Create {
-        at_context: "https://www.w3.org/ns/activitystreams".to_string(),
-        id: format!(
-            "https://ap.podcastindex.org/notes?id={}&statusid={}&resource=activity",
-            podcast_guid,
-            SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()
-        ).to_string(),
-        r#type: "Create".to_string(),
-        actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-        published: iso8601(SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()),
-        to: vec!(
-            "https://www.w3.org/ns/activitystreams#Public".to_string()
-        ),
-        cc: None,
-        object: Object {
-            id: format!(
-                "https://ap.podcastindex.org/notes?id={}&statusid={}&resource=post",
-                podcast_guid,
-                SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()
-            ).to_string(),
-            r#type: "Note".to_string(),
-            summary: None,
-            inReplyTo: None,
-            published: iso8601(SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()),
-            url: format!(
-                "https://ap.podcastindex.org/notes?id={}&statusid={}&resource=public",
-                podcast_guid,
-                SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()
-            ).to_string(),
-            attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
-            to: vec!(
-                "https://www.w3.org/ns/activitystreams#Public".to_string()
-            ),
-            cc: None,
-            sensitive: false,
-            conversation: format!(
-                "tag:ap.podcastindex.org,{}:objectId={}:objectType=Conversation",
-                iso8601(SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()),
-                SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()
-            ).to_string(),
-            content: format!("{}", note),
-            attachment: vec!(),
-        },
+    //##: Store the note first so that its id can be dereferenced from the /notes endpoint
+    let mut note_record = NoteRecord {
+        id: 0,
+        pcid: podcast_guid,
+        content: note,
+        in_reply_to: in_reply_to.unwrap_or("".to_string()),
+        published: SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs(),
     };
+    note_record.id = dbif::add_note_to_db(&AP_DATABASE_FILE.to_string(), note_record.clone())?;
 
-    //##: Is this in reply to another post
-    if in_reply_to.is_some() {
-        create_action_object.object.inReplyTo = Some(in_reply_to.unwrap());
-    }
+    //##: Construct the note object to send
+    let create_action_object = ap_build_note_create(&note_record);
 
     //##: Convert the note create action to JSON and send
-    let create_json;
-    match serde_json::to_string_pretty(&create_action_object) {
+    let create_json = match serde_json::to_string_pretty(&create_action_object) {
         Ok(json_result) => {
-            create_json = json_result;
+            json_result
         }
         Err(e) => {
             eprintln!("Response prep error: [{:#?}].\n", e);
-            return Err(Box::new(HydraError(format!("Error building create note request json: [{}]", e).into())));
-        }
-    }
-
-    //##: Build the http signing headers
-    let key_id = format!("https://ap.podcastindex.org/podcasts?id={}#main-key", podcast_guid);
-    let http_signature_headers;
-    match http_signature::create_http_signature(
-        http::Method::POST,
-        &inbox_url,
-        &create_json.clone(),
-        &private_key,
-        &key_id,
-    ) {
-        Ok(sig_headers) => {
-            http_signature_headers = sig_headers;
-        }
-        Err(e) => {
-            return Err(Box::new(HydraError(format!("Could not build http signature headers: [{}]", e).into())));
+            return Err(Box::new(HydraError(format!("Error building create note request json: [{}]", e))));
         }
-    }
-
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static("Podcast Index AP/v0.1.2a"));
-    headers.insert("Accept", header::HeaderValue::from_static("application/activity+json"));
-    headers.insert("Content-type", header::HeaderValue::from_static("application/activity+json"));
-    headers.insert("date", header::HeaderValue::from_str(&http_signature_headers.date).unwrap());
-    headers.insert("host", header::HeaderValue::from_str(&http_signature_headers.host).unwrap());
-    headers.insert("digest", header::HeaderValue::from_str(&http_signature_headers.digest.unwrap()).unwrap());
-    headers.insert("signature", header::HeaderValue::from_str(&http_signature_headers.signature).unwrap());
-    let client = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()
-        .unwrap();
+    };
 
-    //##: Send the Accept request
-    println!("  NOTE SENT: [{}|{}|{}]", podcast_guid, note, inbox_url.as_str());
-    let res = client
-        .post(inbox_url.as_str())
-        .body(create_json)
-        .send();
-    match res {
-        Ok(res) => {
-            println!("  Response: [{:#?}]", res);
-            let res_body = res.text()?;
-            println!("  Body: [{:#?}]", res_body);
-            return Ok(res_body);
-        }
-        Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error sending episode create note request: [{}]", e).into())));
-        }
-    }
+    //##: Queue it up for the delivery worker, which signs and sends it
+    println!("  NOTE QUEUED: [{}|{}]", podcast_guid, inbox_url.as_str());
+    ap_queue_delivery(podcast_guid, inbox_url, create_json)
 }
 
-pub fn ap_block_send_episode_note(
+pub fn ap_send_episode_note(
     podcast_guid: u64,
     episode: &PIItem,
     inbox_url: String,
     requested: bool,
     in_reply_to: Option<String>,
-) -> Result<String, Box<dyn Error>> {
+) -> Result<u64, Box<dyn Error>> {
     println!("  AP Sending create episode note from actor: {}", podcast_guid);
 
-    //##: Get actor keys for guid
-    let actor_keys = ap_get_actor_keys(podcast_guid).unwrap();
-
-    //##: Decode the private key for the podcast actor
-    let private_key;
-    match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
-        Ok(pem_decoded_privkey) => {
-            private_key = pem_decoded_privkey;
-        }
-        Err(e) => {
-            return Err(Box::new(HydraError(format!("Error decoding private key: [{}]", e).into())));
-        }
-    }
-
     //##: If this object was built by user requesting it then it needs a timestamp parameter in it's object id so
     //##: that it will show up as a new object in their AP timeline.  Otherwise it just comes in as an old post and
     //##: gets sorted below the fold
@@ -2636,13 +3750,14 @@ pub fn ap_block_send_episode_note(
     let mut create_action_object = Create {
         at_context: "https://www.w3.org/ns/activitystreams".to_string(),
         id: format!(
-            "https://ap.podcastindex.org/episodes?id={}&statusid={}&resource=activity{}",
+            "{}/episodes?id={}&statusid={}&resource=activity{}",
+            ap_base_url(),
             podcast_guid,
             episode.guid,
             timestamp_param
         ).to_string(),
         r#type: "Create".to_string(),
-        actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+        actor: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
         published: iso8601(episode.datePublished),
         to: vec!(
             "https://www.w3.org/ns/activitystreams#Public".to_string()
@@ -2652,102 +3767,41 @@ pub fn ap_block_send_episode_note(
     };
 
     //##: Is this in reply to another post
-    if in_reply_to.is_some() {
-        create_action_object.object.inReplyTo = Some(in_reply_to.unwrap());
+    if let Some(in_reply_to) = in_reply_to {
+        create_action_object.object.inReplyTo = Some(in_reply_to);
     }
 
     //##: Convert the note create action to JSON and send
-    let create_json;
-    match serde_json::to_string_pretty(&create_action_object) {
+    let create_json = match serde_json::to_string_pretty(&create_action_object) {
         Ok(json_result) => {
-            create_json = json_result;
+            json_result
         }
         Err(e) => {
             eprintln!("Response prep error: [{:#?}].\n", e);
-            return Err(Box::new(HydraError(format!("Error building create note request json: [{}]", e).into())));
-        }
-    }
-
-    //##: Build the http signing headers
-    let key_id = format!("https://ap.podcastindex.org/podcasts?id={}#main-key", podcast_guid);
-    let http_signature_headers;
-    match http_signature::create_http_signature(
-        http::Method::POST,
-        &inbox_url,
-        &create_json.clone(),
-        &private_key,
-        &key_id,
-    ) {
-        Ok(sig_headers) => {
-            http_signature_headers = sig_headers;
-        }
-        Err(e) => {
-            return Err(Box::new(HydraError(format!("Could not build http signature headers: [{}]", e).into())));
+            return Err(Box::new(HydraError(format!("Error building create note request json: [{}]", e))));
         }
-    }
-
-    //##: Build the query with the required headers
-    let mut headers = header::HeaderMap::new();
-    headers.insert("User-Agent", header::HeaderValue::from_static("Podcast Index AP/v0.1.2a"));
-    headers.insert("Accept", header::HeaderValue::from_static("application/activity+json"));
-    headers.insert("Content-type", header::HeaderValue::from_static("application/activity+json"));
-    headers.insert("date", header::HeaderValue::from_str(&http_signature_headers.date).unwrap());
-    headers.insert("host", header::HeaderValue::from_str(&http_signature_headers.host).unwrap());
-    headers.insert("digest", header::HeaderValue::from_str(&http_signature_headers.digest.unwrap()).unwrap());
-    headers.insert("signature", header::HeaderValue::from_str(&http_signature_headers.signature).unwrap());
-    let client = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()
-        .unwrap();
+    };
 
-    //##: Send the Accept request
-    println!("  EPISODE NOTE SENT: [{}|{}|{}]", podcast_guid, episode.guid, inbox_url.as_str());
-    let res = client
-        .post(inbox_url.as_str())
-        .body(create_json)
-        .send();
-    match res {
-        Ok(res) => {
-            println!("  Response: [{:#?}]", res);
-            let res_body = res.text()?;
-            println!("  Body: [{:#?}]", res_body);
-            return Ok(res_body);
-        }
-        Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error sending episode create note request: [{}]", e).into())));
-        }
-    }
+    //##: Queue it up for the delivery worker, which signs and sends it
+    println!("  EPISODE NOTE QUEUED: [{}|{}]", podcast_guid, inbox_url.as_str());
+    ap_queue_delivery(podcast_guid, inbox_url, create_json)
 }
 
-pub fn ap_block_send_live_note(podcast_guid: u64, episode: &PILiveItem, inbox_url: String) -> Result<String, Box<dyn Error>> {
+pub fn ap_send_live_note(podcast_guid: u64, episode: &PILiveItem, inbox_url: String) -> Result<u64, Box<dyn Error>> {
     println!("  AP Sending create episode note from actor: {}", podcast_guid);
 
-    //##: Get actor keys for guid
-    let actor_keys = ap_get_actor_keys(podcast_guid).unwrap();
-
-    //##: Decode the private key for the podcast actor
-    let private_key;
-    match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
-        Ok(pem_decoded_privkey) => {
-            private_key = pem_decoded_privkey;
-        }
-        Err(e) => {
-            return Err(Box::new(HydraError(format!("Error decoding private key: [{}]", e).into())));
-        }
-    }
-
     //##: Construct the episode note object to send
     let create_action_object = Create {
         at_context: "https://www.w3.org/ns/activitystreams".to_string(),
         id: format!(
-            "https://ap.podcastindex.org/episodes?id={}&statusid={}&date={}&type=live&resource=activity",
+            "{}/episodes?id={}&statusid={}&date={}&type=live&resource=activity",
+            ap_base_url(),
             podcast_guid,
             episode.guid,
             episode.datePublished
         ).to_string(),
         r#type: "Create".to_string(),
-        actor: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+        actor: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
         published: iso8601(episode.datePublished),
         to: vec!(
             "https://www.w3.org/ns/activitystreams#Public".to_string()
@@ -2755,7 +3809,8 @@ pub fn ap_block_send_live_note(podcast_guid: u64, episode: &PILiveItem, inbox_ur
         cc: None,
         object: Object {
             id: format!(
-                "https://ap.podcastindex.org/episodes?id={}&statusid={}&date={}&type=live&resource=post",
+                "{}/episodes?id={}&statusid={}&date={}&type=live&resource=post",
+                ap_base_url(),
                 podcast_guid,
                 episode.guid,
                 episode.datePublished
@@ -2765,19 +3820,21 @@ pub fn ap_block_send_live_note(podcast_guid: u64, episode: &PILiveItem, inbox_ur
             inReplyTo: None,
             published: iso8601(episode.datePublished),
             url: format!(
-                "https://ap.podcastindex.org/episodes?id={}&statusid={}&date={}&type=live&resource=public",
+                "{}/episodes?id={}&statusid={}&date={}&type=live&resource=public",
+                ap_base_url(),
                 podcast_guid,
                 episode.guid,
                 episode.datePublished
             ).to_string(),
-            attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+            attributedTo: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
             to: vec!(
                 "https://www.w3.org/ns/activitystreams#Public".to_string()
             ),
             cc: None,
             sensitive: false,
             conversation: format!(
-                "tag:ap.podcastindex.org,{}:objectId={}:objectType=Conversation",
+                "tag:{},{}:objectId={}:objectType=Conversation",
+                ap_host(),
                 iso8601(episode.datePublished),
                 episode.guid
             ).to_string(),
@@ -2818,34 +3875,260 @@ pub fn ap_block_send_live_note(podcast_guid: u64, episode: &PILiveItem, inbox_ur
         },
     };
     //##: Convert the note create action to JSON and send
-    let create_json;
-    match serde_json::to_string_pretty(&create_action_object) {
+    let create_json = match serde_json::to_string_pretty(&create_action_object) {
         Ok(json_result) => {
-            create_json = json_result;
+            json_result
         }
         Err(e) => {
             eprintln!("Response prep error: [{:#?}].\n", e);
-            return Err(Box::new(HydraError(format!("Error building create note request json: [{}]", e).into())));
+            return Err(Box::new(HydraError(format!("Error building create note request json: [{}]", e))));
+        }
+    };
+
+    //##: Queue it up for the delivery worker, which signs and sends it
+    println!("  LIVE NOTE QUEUED: [{}|{}]", podcast_guid, inbox_url.as_str());
+    ap_queue_delivery(podcast_guid, inbox_url, create_json)
+}
+
+//##: Work out which episodes are new since the tracker last looked at this podcast, going by the publish time
+//##: of the newest one it has handled and the guids it has already handled at that time
+pub fn ap_new_episodes<'a>(items: &'a [PIItem], actor: &ActorRecord, seen_guids: &[String]) -> NewEpisodes<'a> {
+    select_new_episodes(
+        items,
+        actor.last_episode_published,
+        &actor.last_episode_guid,
+        seen_guids,
+        AP_TRACKER_MAX_NEW_EPISODES.load(Ordering::Relaxed) as usize,
+        ap_episode_backlog_policy(),
+    )
+}
+
+fn select_new_episodes<'a>(
+    items: &'a [PIItem],
+    last_published: u64,
+    last_guid: &str,
+    seen_guids: &[String],
+    max_new: usize,
+    policy: EpisodeBacklogPolicy,
+) -> NewEpisodes<'a> {
+    let mut episodes: Vec<&PIItem> = items.iter().collect();
+    episodes.sort_by_key(|episode| episode.datePublished);
+
+    let mut new_episodes = NewEpisodes {
+        announce: Vec::new(),
+        seen: Vec::new(),
+        last_published,
+    };
+    if episodes.is_empty() {
+        return new_episodes;
+    }
+
+    //##: Podcasts tracked before there was a high water mark only have the guid of the last episode sent out.  If
+    //##: that isn't in the list either, go back to just announcing the newest episode
+    let mut since = last_published;
+    if last_published == 0 {
+        match episodes.iter().find(|episode| episode.guid == last_guid) {
+            Some(last_episode) => {
+                since = last_episode.datePublished;
+            }
+            None => {
+                let newest_episode = episodes[episodes.len() - 1];
+                new_episodes.announce.push(newest_episode);
+                new_episodes.seen = episodes.clone();
+                new_episodes.last_published = newest_episode.datePublished;
+                return new_episodes;
+            }
+        }
+    }
+
+    let unseen: Vec<&PIItem> = episodes.into_iter()
+        .filter(|episode| episode.datePublished >= since)
+        .filter(|episode| episode.guid != last_guid && !seen_guids.contains(&episode.guid))
+        .collect();
+
+    let announce_count;
+    if unseen.len() <= max_new {
+        announce_count = unseen.len();
+    } else {
+        match policy {
+            EpisodeBacklogPolicy::Recent => {
+                announce_count = max_new;
+            }
+            EpisodeBacklogPolicy::Latest => {
+                announce_count = 1;
+            }
+            EpisodeBacklogPolicy::Skip => {
+                announce_count = 0;
+            }
+        }
+    }
+
+    new_episodes.announce = unseen[unseen.len() - announce_count..].to_vec();
+    new_episodes.last_published = unseen.iter().map(|episode| episode.datePublished).max().unwrap_or(0).max(since);
+    new_episodes.seen = unseen;
+
+    new_episodes
+}
+
+//##: Split a batch of the index's most recent episodes up by podcast.  None if the oldest of them is newer than
+//##: the last time we looked, since then there could be episodes in between that didn't make it into the batch
+pub fn ap_group_recent_episodes(items: Vec<PIItem>, since: u64) -> Option<HashMap<u64, Vec<PIItem>>> {
+    let oldest_published = items.iter().map(|episode| episode.datePublished).min().unwrap_or(0);
+    if since == 0 || oldest_published > since {
+        return None;
+    }
+
+    let mut episodes_by_feed: HashMap<u64, Vec<PIItem>> = HashMap::new();
+    for episode in items {
+        episodes_by_feed.entry(episode.feedId).or_default().push(episode);
+    }
+
+    Some(episodes_by_feed)
+}
+
+//##: Result of one attempt at posting a queued activity to a remote inbox
+#[derive(Debug, PartialEq)]
+enum DeliveryOutcome {
+    Delivered,
+    Retry(String),
+    Failed(String),
+}
+
+//##: Save an activity to the deliveries table so the delivery worker can send it
+pub fn ap_queue_delivery(podcast_guid: u64, inbox_url: String, activity: String) -> Result<u64, Box<dyn Error>> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs();
+    dbif::add_delivery_to_db(&AP_DATABASE_FILE.to_string(), DeliveryRecord {
+        id: 0,
+        pcid: podcast_guid,
+        inbox: inbox_url,
+        activity,
+        attempts: 0,
+        next_attempt: now,
+        created: now,
+        status: "pending".to_string(),
+        last_error: "".to_string(),
+    })
+}
+
+//##: Send every delivery that is due.  Failed attempts are rescheduled with exponential backoff until the
+//##: delivery horizon passes, then marked as failed for good.  Returns how many were delivered
+pub async fn ap_process_deliveries() -> Result<u64, Box<dyn Error>> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs();
+    let deliveries = dbif::get_due_deliveries_from_db(
+        &AP_DATABASE_FILE.to_string(),
+        now,
+        AP_DELIVERY_BATCH_SIZE,
+    )?;
+
+    //##: Keep a few deliveries in flight at once, so one slow inbox doesn't hold up the rest of the batch
+    let mut delivered = 0;
+    let mut queued = deliveries.into_iter();
+    let mut in_flight = tokio::task::JoinSet::new();
+    loop {
+        while in_flight.len() < AP_DELIVERY_CONCURRENCY {
+            match queued.next() {
+                Some(mut delivery) => {
+                    delivery.attempts += 1;
+                    in_flight.spawn(async move {
+                        let outcome = ap_deliver(&delivery).await;
+                        (delivery, outcome)
+                    });
+                }
+                None => {
+                    break;
+                }
+            }
+        }
+
+        let mut delivery;
+        let outcome;
+        match in_flight.join_next().await {
+            Some(Ok((finished_delivery, delivery_outcome))) => {
+                delivery = finished_delivery;
+                outcome = delivery_outcome;
+            }
+            Some(Err(e)) => {
+                //##: The delivery stays due, so it gets picked up again on the next run
+                eprintln!("  DELIVERY TASK FAILED: [{}]", e);
+                continue;
+            }
+            None => {
+                break;
+            }
+        }
+
+        match outcome {
+            DeliveryOutcome::Delivered => {
+                println!("  DELIVERED: [{}|{}|{}]", delivery.id, delivery.pcid, delivery.inbox);
+                delivery.status = "delivered".to_string();
+                delivery.last_error = "".to_string();
+                delivered += 1;
+            }
+            DeliveryOutcome::Retry(e) => {
+                let next_attempt = now + ap_delivery_backoff(delivery.attempts);
+                if next_attempt > delivery.created + AP_DELIVERY_HORIZON.load(Ordering::Relaxed) {
+                    eprintln!("  DELIVERY GAVE UP: [{}|{}|{}]: [{}]", delivery.id, delivery.pcid, delivery.inbox, e);
+                    delivery.status = "failed".to_string();
+                } else {
+                    eprintln!("  DELIVERY RETRY: [{}|{}|{}]: [{}]", delivery.id, delivery.pcid, delivery.inbox, e);
+                    delivery.next_attempt = next_attempt;
+                }
+                delivery.last_error = e;
+            }
+            DeliveryOutcome::Failed(e) => {
+                eprintln!("  DELIVERY FAILED: [{}|{}|{}]: [{}]", delivery.id, delivery.pcid, delivery.inbox, e);
+                delivery.status = "failed".to_string();
+                delivery.last_error = e;
+            }
+        }
+        //##: Keep going on a database error, so the deliveries still in flight get recorded
+        if let Err(e) = dbif::update_delivery_in_db(&AP_DATABASE_FILE.to_string(), delivery) {
+            eprintln!("  Error saving delivery: [{}]", e);
         }
     }
 
+    Ok(delivered)
+}
+
+//##: Sign and post a queued activity.  The signature is made fresh on every attempt so the date header
+//##: stays current
+async fn ap_deliver(delivery: &DeliveryRecord) -> DeliveryOutcome {
+    //##: Get actor keys for guid
+    let actor_keys = match ap_get_actor_keys(delivery.pcid) {
+        Ok(keys) => {
+            keys
+        }
+        Err(e) => {
+            return DeliveryOutcome::Failed(format!("Error getting actor keys: [{}]", e));
+        }
+    };
+
+    //##: Decode the private key for the podcast actor
+    let private_key = match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
+        Ok(pem_decoded_privkey) => {
+            pem_decoded_privkey
+        }
+        Err(e) => {
+            return DeliveryOutcome::Failed(format!("Error decoding private key: [{}]", e));
+        }
+    };
+
     //##: Build the http signing headers
-    let key_id = format!("https://ap.podcastindex.org/podcasts?id={}#main-key", podcast_guid);
-    let http_signature_headers;
-    match http_signature::create_http_signature(
+    let key_id = format!("{}/podcasts?id={}#main-key", ap_base_url(), delivery.pcid);
+    let http_signature_headers = match http_signature::create_http_signature(
         http::Method::POST,
-        &inbox_url,
-        &create_json.clone(),
+        &delivery.inbox,
+        &delivery.activity,
         &private_key,
         &key_id,
     ) {
         Ok(sig_headers) => {
-            http_signature_headers = sig_headers;
+            sig_headers
         }
         Err(e) => {
-            return Err(Box::new(HydraError(format!("Could not build http signature headers: [{}]", e).into())));
+            return DeliveryOutcome::Failed(format!("Could not build http signature headers: [{}]", e));
         }
-    }
+    };
 
     //##: Build the query with the required headers
     let mut headers = header::HeaderMap::new();
@@ -2856,65 +4139,80 @@ pub fn ap_block_send_live_note(podcast_guid: u64, episode: &PILiveItem, inbox_ur
     headers.insert("host", header::HeaderValue::from_str(&http_signature_headers.host).unwrap());
     headers.insert("digest", header::HeaderValue::from_str(&http_signature_headers.digest.unwrap()).unwrap());
     headers.insert("signature", header::HeaderValue::from_str(&http_signature_headers.signature).unwrap());
-    let client = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()
-        .unwrap();
 
-    //##: Send the Accept request
-    println!("  EPISODE NOTE SENT: [{}|{}|{}]", podcast_guid, episode.guid, inbox_url.as_str());
-    let res = client
-        .post(inbox_url.as_str())
-        .body(create_json)
-        .send();
-    match res {
+    //##: Send it
+    match http_client()
+        .post(delivery.inbox.as_str())
+        .headers(headers)
+        .body(delivery.activity.clone())
+        .send()
+        .await
+    {
         Ok(res) => {
-            println!("  Response: [{:#?}]", res);
-            let res_body = res.text()?;
-            println!("  Body: [{:#?}]", res_body);
-            return Ok(res_body);
+            let status = res.status();
+            println!("  Response: [{}]", status);
+            if status.is_success() {
+                return DeliveryOutcome::Delivered;
+            }
+            let res_body = res.text().await.unwrap_or_default();
+            eprintln!("  Body: [{:#?}]", res_body);
+            if ap_delivery_status_is_retryable(status.as_u16()) {
+                return DeliveryOutcome::Retry(format!("Remote inbox returned: [{}]", status));
+            }
+            DeliveryOutcome::Failed(format!("Remote inbox returned: [{}]", status))
         }
         Err(e) => {
-            eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error sending episode create note request: [{}]", e).into())));
+            DeliveryOutcome::Retry(format!("Error sending request: [{}]", e))
         }
     }
 }
 
-pub fn ap_block_get_remote_actor(podcast_guid: u64, actor_url: String) -> Result<Actor, Box<dyn Error>> {
+//##: Rate limiting and server side errors are worth another try, anything else the remote end won't accept
+fn ap_delivery_status_is_retryable(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+//##: Seconds to wait before the next attempt, doubling each time up to a ceiling
+fn ap_delivery_backoff(attempts: u64) -> u64 {
+    let doublings = attempts.saturating_sub(1).min(32) as u32;
+    AP_DELIVERY_RETRY_BASE_SECONDS
+        .saturating_mul(2u64.saturating_pow(doublings))
+        .min(AP_DELIVERY_RETRY_MAX_SECONDS)
+}
+
+pub async fn ap_get_remote_actor(podcast_guid: u64, actor_url: String) -> Result<Actor, Box<dyn Error>> {
     println!("  AP Get Remote Actor: {}", actor_url);
 
-    //##: Get actor keys for guid
-    let actor_keys = ap_get_actor_keys(podcast_guid).unwrap();
+    //##: Sign as the podcast if it already has keys, otherwise as the instance actor.  This runs before the
+    //##: request is authenticated, so it must never create an actor for whatever id was asked for
+    let (signer_guid, actor_keys) = ap_get_signing_keys(podcast_guid)?;
 
     //##: Decode the private key for the podcast actor
-    let private_key;
-    match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
+    let private_key = match crypto_rsa::rsa_private_key_from_pkcs1_pem(&actor_keys.pem_private_key) {
         Ok(pem_decoded_privkey) => {
-            private_key = pem_decoded_privkey;
+            pem_decoded_privkey
         }
         Err(e) => {
-            return Err(Box::new(HydraError(format!("Error decoding private key: [{}]", e).into())));
+            return Err(Box::new(HydraError(format!("Error decoding private key: [{}]", e))));
         }
-    }
+    };
 
     //##: Build the http signing headers
-    let key_id = format!("https://ap.podcastindex.org/podcasts?id={}#main-key", podcast_guid);
-    let http_signature_headers;
-    match http_signature::create_http_signature(
+    let key_id = format!("{}/podcasts?id={}#main-key", ap_base_url(), signer_guid);
+    let http_signature_headers = match http_signature::create_http_signature(
         http::Method::GET,
         &actor_url,
-        &"".to_string(),
+        "",
         &private_key,
         &key_id,
     ) {
         Ok(sig_headers) => {
-            http_signature_headers = sig_headers;
+            sig_headers
         }
         Err(e) => {
-            return Err(Box::new(HydraError(format!("Could not build http signature headers: [{}]", e).into())));
+            return Err(Box::new(HydraError(format!("Could not build http signature headers: [{}]", e))));
         }
-    }
+    };
 
     // //##: Debug
     // println!("  Sig: [{:#?}]", http_signature_headers.digest);
@@ -2927,42 +4225,189 @@ pub fn ap_block_get_remote_actor(podcast_guid: u64, actor_url: String) -> Result
     headers.insert("host", header::HeaderValue::from_str(&http_signature_headers.host).unwrap());
     //headers.insert("digest", header::HeaderValue::from_str(&http_signature_headers.digest.unwrap()).unwrap());
     headers.insert("signature", header::HeaderValue::from_str(&http_signature_headers.signature).unwrap());
-    let client = reqwest::blocking::Client::builder()
-        .default_headers(headers)
-        .build()
-        .unwrap();
 
     //##: Send the Accept request
     println!("  ACTOR REQUEST SENT: [{}]", actor_url.as_str());
-    let res = client
+    let res = http_client()
         .get(actor_url.as_str())
+        .headers(headers)
         .send();
-    match res {
+    match res.await {
         Ok(response) => {
             println!("  Response: [{:#?}]", response);
-            if response.status() >= StatusCode::from_u16(200)? && response.status() <= StatusCode::from_u16(299)? {
-                match serde_json::from_str::<Actor>(response.text().unwrap().as_str()) {
+            if response.status().is_success() {
+                match serde_json::from_str::<Actor>(response.text().await.unwrap_or_default().as_str()) {
                     Ok(actor_data) => {
-                        return Ok(actor_data);
+                        Ok(actor_data)
                     }
                     Err(e) => {
                         eprintln!("Could not parse incoming request: [{}].\n", e);
-                        return Err(Box::new(HydraError(format!("Error parsing remote actor data: [{}]", e).into())));
+                        Err(Box::new(HydraError(format!("Error parsing remote actor data: [{}]", e))))
                     }
                 }
             } else {
-                let res_body = response.text().unwrap();
+                let res_body = response.text().await.unwrap_or_default();
                 eprintln!("  Actor Body: [{:#?}]", res_body);
-                return Err(Box::new(HydraError(format!("Getting remote actor failed.").into())));
+                Err(Box::new(HydraError("Getting remote actor failed.".to_string())))
             }
         }
         Err(e) => {
             eprintln!("  Error: [{}]", e);
-            return Err(Box::new(HydraError(format!("Error getting remote actor: [{}]", e).into())));
+            Err(Box::new(HydraError(format!("Error getting remote actor: [{}]", e))))
+        }
+    }
+}
+
+//##: Webfinger a visitor's own handle and build the url on their home instance that lets them follow the
+//##: given actor.  Instances that don't advertise a subscribe template get Mastodon's authorize_interaction
+async fn ap_get_remote_subscribe_url(handle: &str, actor_url: &str) -> Result<String, Box<dyn Error>> {
+    let (username, domain) = parse_fediverse_handle(handle)
+        .ok_or_else(|| HydraError(format!("Invalid handle: [{}]", handle)))?;
+    let webfinger_url = url::Url::parse_with_params(
+        format!("https://{}/.well-known/webfinger", domain).as_str(),
+        &[("resource", format!("acct:{}@{}", username, domain))],
+    )?;
+
+    let response = http_client()
+        .get(webfinger_url)
+        .header("Accept", "application/jrd+json, application/json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(Box::new(HydraError(format!("Webfinger returned: [{}]", response.status()))));
+    }
+    let webfinger_data = response.json::<Webfinger>().await?;
+
+    for link in webfinger_data.links {
+        if link.rel == "http://ostatus.org/schema/1.0/subscribe" {
+            if let Some(template) = link.template {
+                return Ok(build_subscribe_url(&template, actor_url));
+            }
+        }
+    }
+
+    Ok(build_subscribe_url(&format!("https://{}/authorize_interaction?uri={{uri}}", domain), actor_url))
+}
+
+//##: Check the http signature on an incoming request against the signer's public key and make sure the
+//##: key belongs to the actor named in the activity.  Returns the key that verified it.
+async fn ap_verify_request_signature(
+    podcast_guid: u64,
+    request_parts: &hyper::http::request::Parts,
+    request_body: &str,
+    activity_actor: String,
+) -> Result<RemoteKeyRecord, Box<dyn Error>> {
+
+    //##: The body must be the one that was signed
+    http_signature::verify_digest_header(&request_parts.headers, request_body)?;
+
+    //##: Rebuild the signing string from the request
+    let signature_data = http_signature::parse_http_signature(
+        &request_parts.method,
+        &request_parts.uri,
+        &request_parts.headers,
+    )?;
+
+    //##: Look up the signer's key, from the cache if we have it
+    let mut refreshed = false;
+    let mut remote_key;
+    //##: Keys cached before owners were checked against their servers are looked up again
+    let cached_key = dbif::get_remote_key_from_db(&AP_DATABASE_FILE.to_string(), signature_data.key_id.clone())
+        .ok()
+        .filter(|cached_key| ap_same_origin(&cached_key.keyid, &cached_key.owner));
+    match cached_key {
+        Some(cached_key) => {
+            remote_key = cached_key;
+        }
+        None => {
+            remote_key = ap_get_remote_public_key(podcast_guid, signature_data.key_id.clone()).await?;
+            refreshed = true;
+        }
+    }
+
+    loop {
+        if remote_key.owner != activity_actor {
+            return Err(Box::new(HydraError(format!(
+                "Key owner: [{}] does not match actor: [{}]",
+                remote_key.owner,
+                activity_actor
+            ))));
+        }
+
+        let public_key = crypto_rsa::deserialize_rsa_public_key(&remote_key.pem_public_key)?;
+        match http_signature::verify_http_signature(&signature_data, &public_key) {
+            Ok(_) => {
+                return Ok(remote_key);
+            }
+            Err(e) => {
+                //##: The actor may have rotated their key since we cached it, so fetch it once more
+                if refreshed {
+                    return Err(Box::new(e));
+                }
+                remote_key = ap_get_remote_public_key(podcast_guid, signature_data.key_id.clone()).await?;
+                refreshed = true;
+            }
         }
     }
 }
 
+//##: Fetch the public key for a key id from the remote actor document and cache it
+async fn ap_get_remote_public_key(podcast_guid: u64, key_id: String) -> Result<RemoteKeyRecord, Box<dyn Error>> {
+    let mut actor_url = url::Url::parse(&key_id)?;
+    actor_url.set_fragment(None);
+
+    let remote_actor = ap_get_remote_actor(podcast_guid, actor_url.to_string()).await?;
+    ap_check_remote_key_owner(&key_id, &remote_actor.id, &remote_actor.publicKey.id, &remote_actor.publicKey.owner)?;
+
+    let remote_key = RemoteKeyRecord {
+        keyid: key_id,
+        owner: remote_actor.publicKey.owner,
+        pem_public_key: remote_actor.publicKey.publicKeyPem,
+        fetched: SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs(),
+    };
+    if let Err(e) = dbif::add_remote_key_to_db(&AP_DATABASE_FILE.to_string(), remote_key.clone()) {
+        eprintln!("  Could not cache remote key: [{}]", e);
+    }
+
+    Ok(remote_key)
+}
+
+//##: A key can only speak for the actor whose document it was published in, and that actor has to live on the
+//##: same server as the key.  Otherwise anyone could publish a key naming somebody else as its owner
+fn ap_check_remote_key_owner(key_id: &str, actor_id: &str, public_key_id: &str, owner: &str) -> Result<(), Box<dyn Error>> {
+    if public_key_id != key_id {
+        return Err(Box::new(HydraError(format!(
+            "Remote actor key: [{}] does not match key id: [{}]",
+            public_key_id,
+            key_id
+        ))));
+    }
+    if actor_id != owner {
+        return Err(Box::new(HydraError(format!(
+            "Key owner: [{}] is not the actor it was published by: [{}]",
+            owner,
+            actor_id
+        ))));
+    }
+
+    if !ap_same_origin(key_id, owner) {
+        return Err(Box::new(HydraError(format!(
+            "Key: [{}] is not on the same server as its owner: [{}]",
+            key_id,
+            owner
+        ))));
+    }
+
+    Ok(())
+}
+
+fn ap_same_origin(first_url: &str, second_url: &str) -> bool {
+    match (url::Url::parse(first_url), url::Url::parse(second_url)) {
+        (Ok(first), Ok(second)) => first.origin() == second.origin(),
+        _ => false,
+    }
+}
+
 //##: Construct an ActivityPub note object from a PI API episode object
 pub fn build_episode_note_object(
     episode: &PIItem,
@@ -2975,13 +4420,12 @@ pub fn build_episode_note_object(
     let mut episode_social_interact_display = "".to_string();
     match &episode.socialInteract {
         Some(social_interacts) => {
-            for social_interact in social_interacts {
+            if let Some(social_interact) = social_interacts.first() {
                 let episode_social_interact_uri = social_interact.uri.clone().unwrap_or("".to_string());
                 episode_social_interact_display = format!(
                     "<p><a href=\"{}\">Comments Thread</a></p>",
                     episode_social_interact_uri
                 );
-                break;
             }
         }
         None => {
@@ -2993,13 +4437,12 @@ pub fn build_episode_note_object(
     let mut episode_transcript_display = "".to_string();
     match &episode.transcripts {
         Some(transcripts) => {
-            for _transcript in transcripts {
+            if !transcripts.is_empty() {
                 episode_transcript_display = format!(
                     "<p><a href=\"https://steno.fm/show/{}/episode/{}\">Transcript</a></p>",
                     episode.podcastGuid,
-                    general_purpose::STANDARD.encode(&episode.guid.clone().as_bytes())
+                    general_purpose::STANDARD.encode(episode.guid.clone().as_bytes())
                 );
-                break;
             }
         }
         None => {
@@ -3018,9 +4461,10 @@ pub fn build_episode_note_object(
     };
 
     //##: Put it all together
-    return Ok(Object {
+    Ok(Object {
         id: format!(
-            "https://ap.podcastindex.org/episodes?id={}&statusid={}&resource=post{}",
+            "{}/episodes?id={}&statusid={}&resource=post{}",
+            ap_base_url(),
             podcast_guid,
             episode.guid,
             timestamp_param
@@ -3030,19 +4474,21 @@ pub fn build_episode_note_object(
         inReplyTo: None,
         published: iso8601(episode.datePublished),
         url: format!(
-            "https://ap.podcastindex.org/episodes?id={}&statusid={}&resource=public{}",
+            "{}/episodes?id={}&statusid={}&resource=public{}",
+            ap_base_url(),
             podcast_guid,
             episode.guid,
             timestamp_param
         ).to_string(),
-        attributedTo: format!("https://ap.podcastindex.org/podcasts?id={}", podcast_guid).to_string(),
+        attributedTo: format!("{}/podcasts?id={}", ap_base_url(), podcast_guid).to_string(),
         to: vec!(
             "https://www.w3.org/ns/activitystreams#Public".to_string()
         ),
         cc: None,
         sensitive: false,
         conversation: format!(
-            "tag:ap.podcastindex.org,{}:objectId={}:objectType=Conversation",
+            "tag:{},{}:objectId={}:objectType=Conversation",
+            ap_host(),
             iso8601(episode.datePublished),
             episode.guid
         ).to_string(),
@@ -3105,10 +4551,94 @@ pub fn build_episode_note_object(
                 description: Some(format!("Episode artwork for {}", episode.title).to_string()),
             }
         ),
-    });
+    })
 }
 
 //Utilities --------------------------------------------------------------------------------------------------
+//##: One pooled client for every outbound request, so connections to busy instances get reused
+pub fn http_client() -> &'static reqwest::Client {
+    HTTP_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(AP_HTTP_TIMEOUT_SECONDS))
+            .build()
+            .unwrap()
+    })
+}
+
+//##: Set the public base url (scheme and host) this bridge is reachable at.  Call once at startup before
+//##: any urls are built, otherwise the default of https://ap.podcastindex.org is used
+pub fn set_ap_base_url(base_url: &str) -> Result<(), Box<dyn Error>> {
+    let parsed_url = url::Url::parse(base_url)?;
+    if parsed_url.scheme() != "https" && parsed_url.scheme() != "http" {
+        return Err(Box::new(HydraError(format!("Base url must be http(s): [{}]", base_url))));
+    }
+    if parsed_url.host_str().is_none() {
+        return Err(Box::new(HydraError(format!("Base url has no host: [{}]", base_url))));
+    }
+
+    let base_url = base_url.trim_end_matches('/').to_string();
+    if AP_BASE_URL.set(base_url).is_err() {
+        return Err(Box::new(HydraError("Base url was already set.".to_string())));
+    }
+
+    Ok(())
+}
+
+//##: Only give out follower counts, not the list of who follows a podcast
+pub fn set_ap_hide_followers(hide_followers: bool) {
+    AP_HIDE_FOLLOWERS.store(hide_followers, Ordering::Relaxed);
+}
+
+pub fn set_ap_delivery_horizon(hours: u64) {
+    AP_DELIVERY_HORIZON.store(hours * 3600, Ordering::Relaxed);
+}
+
+//##: Most new episodes of one podcast to announce in a single tracker run before the backlog policy kicks in
+pub fn set_ap_tracker_max_new_episodes(max_new: u64) {
+    AP_TRACKER_MAX_NEW_EPISODES.store(max_new, Ordering::Relaxed);
+}
+
+pub fn set_ap_episode_backlog_policy(policy: &str) -> Result<(), Box<dyn Error>> {
+    let backlog_policy = match policy.to_lowercase().as_str() {
+        "recent" => {
+            EpisodeBacklogPolicy::Recent
+        }
+        "latest" => {
+            EpisodeBacklogPolicy::Latest
+        }
+        "skip" => {
+            EpisodeBacklogPolicy::Skip
+        }
+        _ => {
+            return Err(Box::new(HydraError(format!("Unknown backlog policy: [{}]", policy))));
+        }
+    };
+
+    if AP_EPISODE_BACKLOG_POLICY.set(backlog_policy).is_err() {
+        return Err(Box::new(HydraError("Backlog policy was already set.".to_string())));
+    }
+
+    Ok(())
+}
+
+pub fn ap_episode_backlog_policy() -> EpisodeBacklogPolicy {
+    AP_EPISODE_BACKLOG_POLICY.get().copied().unwrap_or(EpisodeBacklogPolicy::Recent)
+}
+
+pub fn ap_base_url() -> &'static str {
+    AP_BASE_URL.get().map(|base_url| base_url.as_str()).unwrap_or(AP_DEFAULT_BASE_URL)
+}
+
+//##: The domain part of the base url, used for acct: handles and tag: uris
+pub fn ap_host() -> String {
+    let base_url = url::Url::parse(ap_base_url()).unwrap();
+    let host = base_url.host_str().unwrap_or("").to_string();
+    match base_url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    }
+}
+
 fn iso8601(utime: u64) -> String {
 
     // Create DateTime from SystemTime
@@ -3118,6 +4648,233 @@ fn iso8601(utime: u64) -> String {
     datetime.format("%+").to_string()
 }
 
+//##: Outbox cursors look like <datePublished>_<episode id>, so episodes published in the same second still
+//##: have an order between them.  A bare timestamp, as handed out by older pages, sorts with `bare_id`
+fn parse_outbox_cursor(cursor: &str, bare_id: u64) -> Option<(u64, u64)> {
+    match cursor.split_once('_') {
+        Some((date, id)) => {
+            Some((date.parse::<u64>().ok()?, id.parse::<u64>().ok()?))
+        }
+        None => {
+            Some((cursor.parse::<u64>().ok()?, bare_id))
+        }
+    }
+}
+
+//##: Ask the index for just what a page needs.  The first page only needs one more episode than it shows, and
+//##: newer pages only need episodes from the cursor on.  The index can't list a feed's episodes from before
+//##: a date, so for older pages we count the episodes from the cursor on and take only enough of the newest
+//##: to reach one past the end of the page.  paginate_by_date() cuts out the page itself
+async fn ap_get_outbox_episodes(
+    pi_client: &dyn PodcastIndexClient,
+    podcast_guid: &str,
+    max_id: Option<(u64, u64)>,
+    min_id: Option<(u64, u64)>,
+) -> PIResult<PIEpisodes> {
+    let first_page_max = AP_OUTBOX_PAGE_SIZE as u64 + 1;
+
+    if podcast_guid == "0" {
+        if max_id.is_none() && min_id.is_none() {
+            return pi_client.get_recent_episodes(first_page_max).await;
+        }
+        return pi_client.get_recent_episodes(AP_OUTBOX_MAX_RECENT_EPISODES).await;
+    }
+
+    if let Some((min_date, _)) = min_id {
+        return pi_client.get_episodes_since(podcast_guid, min_date.saturating_sub(1), AP_OUTBOX_MAX_EPISODES).await;
+    }
+    if let Some((max_date, _)) = max_id {
+        let newer = pi_client.get_episodes_since(podcast_guid, max_date, AP_OUTBOX_MAX_EPISODES).await?;
+        let window = (newer.items.len() as u64 + first_page_max).min(AP_OUTBOX_MAX_EPISODES);
+        return pi_client.get_episodes(podcast_guid, window).await;
+    }
+
+    return pi_client.get_episodes(podcast_guid, first_page_max).await;
+}
+
+//##: Pick one page out of a newest-first list using cursors, which are compared by publish date first.  With
+//##: max_id the page holds the items just older than the cursor, with min_id the items just newer than it.
+//##: Also returns whether there are older and newer items outside of the page
+fn paginate_by_date<T, K: Ord + Copy>(
+    mut items: Vec<T>,
+    date: impl Fn(&T) -> K,
+    max_id: Option<K>,
+    min_id: Option<K>,
+    page_size: usize,
+) -> (Vec<T>, bool, bool) {
+    items.sort_by_key(|item| std::cmp::Reverse(date(item)));
+
+    if let Some(max_id) = max_id {
+        let has_newer = items.iter().any(|item| date(item) >= max_id);
+        let mut page: Vec<T> = items.into_iter().filter(|item| date(item) < max_id).collect();
+        let has_older = page.len() > page_size;
+        page.truncate(page_size);
+        return (page, has_older, has_newer);
+    }
+
+    if let Some(min_id) = min_id {
+        let has_older = items.iter().any(|item| date(item) <= min_id);
+        let mut page: Vec<T> = items.into_iter().filter(|item| date(item) > min_id).collect();
+        let has_newer = page.len() > page_size;
+        let skip = page.len().saturating_sub(page_size);
+        page.drain(..skip);
+        return (page, has_older, has_newer);
+    }
+
+    let has_older = items.len() > page_size;
+    items.truncate(page_size);
+    (items, has_older, false)
+}
+
+//##: Split a fediverse handle like @user@example.com into the user and the domain.  The domain has to be a
+//##: plain host name since it ends up in a url we fetch, and not an ip address that could point inside our network
+fn parse_fediverse_handle(handle: &str) -> Option<(String, String)> {
+    let handle = handle.trim().trim_start_matches("acct:").trim_start_matches('@');
+    let (username, domain) = handle.split_once('@')?;
+    if username.is_empty() || domain.is_empty() {
+        return None;
+    }
+    match url::Url::parse(format!("https://{}/", domain).as_str()) {
+        Ok(domain_url) => {
+            match domain_url.host() {
+                Some(url::Host::Domain(host)) if host == domain.to_lowercase() => {}
+                _ => {
+                    return None;
+                }
+            }
+        }
+        Err(_) => {
+            return None;
+        }
+    }
+
+    Some((username.to_string(), domain.to_lowercase()))
+}
+
+fn parse_webfinger_resource(resource: &str, base_url: &str, host: &str) -> Option<PodcastLookup> {
+    let resource = resource.trim();
+
+    //##: Our own actor urls carry the feed id
+    if resource.starts_with(&format!("{}/podcasts?", base_url)) {
+        let podcast_id = url::Url::parse(resource).ok()?
+            .query_pairs()
+            .find(|pair| pair.0 == "id")
+            .map(|pair| pair.1.to_string())?;
+        if podcast_id.parse::<u64>().is_err() {
+            return None;
+        }
+        return Some(PodcastLookup::FeedId(podcast_id));
+    }
+
+    //##: Any other url is taken to be the podcast's feed
+    if resource.starts_with("https://") || resource.starts_with("http://") {
+        return Some(PodcastLookup::FeedUrl(resource.to_string()));
+    }
+
+    //##: Otherwise it's an account on our own domain
+    let mut account = resource.trim_start_matches("acct:");
+    if account.matches('@').count() > 1 {
+        account = account.trim_start_matches('@');
+    }
+    let mut account_parts = account.splitn(2, '@');
+    let username = account_parts.next()?.to_string();
+    if let Some(domain) = account_parts.next() {
+        if !domain.eq_ignore_ascii_case(host) {
+            return None;
+        }
+    }
+    if username.is_empty() {
+        return None;
+    }
+    if username.chars().all(|c| c.is_ascii_digit()) {
+        return Some(PodcastLookup::FeedId(username));
+    }
+    if is_podcast_guid(&username) {
+        return Some(PodcastLookup::Guid(username.to_lowercase()));
+    }
+
+    Some(PodcastLookup::Slug(slugify(&username)))
+}
+
+fn is_podcast_guid(text: &str) -> bool {
+    let groups: Vec<&str> = text.split('-').collect();
+    if groups.len() != 5 {
+        return false;
+    }
+    let lengths = [8, 4, 4, 4, 12];
+
+    groups.iter().zip(lengths.iter()).all(|(group, length)| {
+        group.len() == *length && group.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+fn slugify(text: &str) -> String {
+    let mut slug = "".to_string();
+    for c in text.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_end_matches('-').to_string()
+}
+
+fn accepts_activity_json(ctx: &Context) -> bool {
+    ctx.req.headers()
+        .get("accept")
+        .and_then(|accept| accept.to_str().ok())
+        .map(media_type_is_activity_json)
+        .unwrap_or(false)
+}
+
+fn media_type_is_activity_json(accept: &str) -> bool {
+    let accept = accept.to_lowercase();
+    accept.contains("application/activity+json") || accept.contains("application/ld+json")
+}
+
+fn html_strip_tags(text: &str) -> String {
+    let mut stripped = "".to_string();
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    stripped.trim().to_string()
+}
+
+fn build_subscribe_url(template: &str, uri: &str) -> String {
+    template.replace("{uri}", &urlencoding::encode(uri))
+}
+
+fn html_escape(text: &str) -> String {
+    text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+//##: Feed and remote data can carry javascript: and other schemes, so only plain web links are put in an href or src
+fn is_web_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+fn html_escape_url(url: &str) -> String {
+    if !is_web_url(url) {
+        return "".to_string();
+    }
+
+    html_escape(url)
+}
+
 fn get_host_from_url(url: String) -> String {
     let request_url_object = url::Url::parse(&url).unwrap();
     request_url_object.host_str()
@@ -3134,7 +4891,7 @@ fn get_id_from_url(url: String) -> String {
         }
     }
 
-    return "".to_string();
+    "".to_string()
 }
 
 fn get_statusid_from_url(url: String) -> String {
@@ -3145,5 +4902,528 @@ fn get_statusid_from_url(url: String) -> String {
         }
     }
 
-    return "".to_string();
+    "".to_string()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppState;
+    use crate::podcastindex::PodcastIndexFixtures;
+    use std::sync::Arc;
+
+    fn fixture_context(uri: &str) -> Context {
+        let pi_client: Arc<dyn PodcastIndexClient> = Arc::new(PodcastIndexFixtures::new(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/sample_data/podcastindex")
+        ));
+        Context::new(
+            AppState {
+                state_thing: "".to_string(),
+                remote_ip: "127.0.0.1".to_string(),
+            },
+            hyper::Request::get(uri).body(hyper::Body::empty()).unwrap(),
+            route_recognizer::Params::new(),
+            pi_client,
+            "test".to_string(),
+        )
+    }
+
+    async fn response_json(response: Response) -> serde_json::Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    //##: An inbox POST signed by the remote actor with the given key.  The signature covers the query, the same
+    //##: as the request-target ap_verify_request_signature() rebuilds
+    fn signed_inbox_context(key_id: &str, private_key: &RsaPrivateKey, body: &str) -> Context {
+        let request_path = "/inbox?id=920666";
+        let signature_headers = http_signature::create_http_signature(
+            http::Method::POST,
+            &format!("https://ap.podcastindex.org{}", request_path),
+            body,
+            private_key,
+            key_id,
+        ).unwrap();
+        let digest = signature_headers.digest.unwrap();
+        let message = format!(
+            "(request-target): post {}\nhost: {}\ndate: {}\ndigest: {}",
+            request_path,
+            signature_headers.host,
+            signature_headers.date,
+            digest
+        );
+        let signature = crypto_rsa::create_rsa_sha256_signature(private_key, &message).unwrap();
+
+        let mut context = fixture_context(request_path);
+        *context.req.method_mut() = hyper::Method::POST;
+        *context.req.body_mut() = hyper::Body::from(body.to_string());
+        let headers = context.req.headers_mut();
+        headers.insert("host", signature_headers.host.parse().unwrap());
+        headers.insert("date", signature_headers.date.parse().unwrap());
+        headers.insert("digest", digest.parse().unwrap());
+        headers.insert("signature", format!(
+            "keyId=\"{}\",algorithm=\"rsa-sha256\",headers=\"(request-target) host date digest\",signature=\"{}\"",
+            key_id,
+            general_purpose::STANDARD.encode(signature)
+        ).parse().unwrap());
+
+        context
+    }
+
+    fn episode_reply_count() -> usize {
+        dbif::get_replies_from_db_by_episode(&AP_DATABASE_FILE.to_string(), 920666, "PC20160".to_string())
+            .unwrap()
+            .len()
+    }
+
+    #[tokio::test]
+    async fn test_inbox_reply_ownership() {
+        let _ = std::fs::create_dir_all(std::path::Path::new(AP_DATABASE_FILE).parent().unwrap());
+        let _ = std::fs::remove_file(AP_DATABASE_FILE);
+        dbif::create_database(&AP_DATABASE_FILE.to_string()).unwrap();
+
+        //##: Two remote actors whose keys we already hold
+        let private_key = crypto_rsa::generate_weak_rsa_key().unwrap();
+        let pem_public_key = crypto_rsa::rsa_public_key_to_pkcs8_pem(&RsaPublicKey::from(&private_key)).unwrap();
+        let alice = "https://remote.example/users/alice";
+        let bob = "https://remote.example/users/bob";
+        for actor in [alice, bob] {
+            dbif::add_remote_key_to_db(&AP_DATABASE_FILE.to_string(), RemoteKeyRecord {
+                keyid: format!("{}#main-key", actor),
+                owner: actor.to_string(),
+                pem_public_key: pem_public_key.clone(),
+                fetched: 0,
+            }).unwrap();
+        }
+
+        let create_body = |sender: &str, attributed_to: &str| serde_json::json!({
+            "id": format!("{}/statuses/1/activity", sender),
+            "type": "Create",
+            "actor": sender,
+            "object": {
+                "id": format!("{}/statuses/1", alice),
+                "type": "Note",
+                "attributedTo": attributed_to,
+                "inReplyTo": "https://ap.podcastindex.org/episodes?id=920666&statusid=PC20160&resource=post",
+                "content": "<p>Great episode</p>",
+            },
+        }).to_string();
+        let delete_body = |sender: &str| serde_json::json!({
+            "id": format!("{}/statuses/1#delete", sender),
+            "type": "Delete",
+            "actor": sender,
+            "object": format!("{}/statuses/1", alice),
+        }).to_string();
+
+        //##: Bob can't post a reply in Alice's name
+        let response = inbox(signed_inbox_context(&format!("{}#main-key", bob), &private_key, &create_body(bob, alice))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(episode_reply_count(), 0);
+
+        let response = inbox(signed_inbox_context(&format!("{}#main-key", alice), &private_key, &create_body(alice, alice))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(episode_reply_count(), 1);
+        let replies_collection = ap_build_replies_collection(920666, "PC20160").unwrap();
+        assert_eq!(replies_collection.totalItems, Some(1));
+        assert_eq!(replies_collection.first.unwrap().items, Some(vec![format!("{}/statuses/1", alice)]));
+
+        //##: Nor delete Alice's reply, only she can
+        let response = inbox(signed_inbox_context(&format!("{}#main-key", bob), &private_key, &delete_body(bob))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(episode_reply_count(), 1);
+
+        let response = inbox(signed_inbox_context(&format!("{}#main-key", alice), &private_key, &delete_body(alice))).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(episode_reply_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_webfinger_with_fixtures() {
+        //##: Asked for by guid, answered with the feed id account
+        let response = webfinger(fixture_context(
+            "/.well-known/webfinger?resource=acct:917393e3-1b1e-5cef-ace4-edaa54e1f810@ap.podcastindex.org"
+        )).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let webfinger_json = response_json(response).await;
+        assert_eq!(webfinger_json["subject"], "acct:920666@ap.podcastindex.org");
+        assert_eq!(webfinger_json["aliases"][0], "https://ap.podcastindex.org/podcasts?id=920666");
+        assert_eq!(webfinger_json["links"][1]["rel"], "self");
+        assert_eq!(webfinger_json["links"][1]["href"], "https://ap.podcastindex.org/podcasts?id=920666");
+        assert_eq!(
+            webfinger_json["links"][2]["href"],
+            "https://noagendaassets.com/enc/1684513486.722_pcifeedimage.png"
+        );
+
+        let response = webfinger(fixture_context(
+            "/.well-known/webfinger?resource=acct:12345@ap.podcastindex.org"
+        )).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response_json(response).await["error"], "Unknown podcast.");
+    }
+
+    #[test]
+    fn test_ap_delivery_backoff() {
+        assert_eq!(ap_delivery_backoff(1), 60);
+        assert_eq!(ap_delivery_backoff(2), 120);
+        assert_eq!(ap_delivery_backoff(4), 480);
+        assert_eq!(ap_delivery_backoff(100), AP_DELIVERY_RETRY_MAX_SECONDS);
+    }
+
+    #[test]
+    fn test_ap_delivery_status_is_retryable() {
+        assert!(ap_delivery_status_is_retryable(429));
+        assert!(ap_delivery_status_is_retryable(502));
+        assert!(!ap_delivery_status_is_retryable(404));
+        assert!(!ap_delivery_status_is_retryable(410));
+    }
+
+    #[test]
+    fn test_parse_fediverse_handle() {
+        let expected = Some(("dave".to_string(), "podcastindex.social".to_string()));
+        assert_eq!(parse_fediverse_handle("dave@podcastindex.social"), expected);
+        assert_eq!(parse_fediverse_handle(" @dave@PodcastIndex.social "), expected);
+        assert_eq!(parse_fediverse_handle("acct:dave@podcastindex.social"), expected);
+        assert_eq!(parse_fediverse_handle("dave"), None);
+        assert_eq!(parse_fediverse_handle("@podcastindex.social"), None);
+        assert_eq!(parse_fediverse_handle("dave@evil.example/path"), None);
+        assert_eq!(parse_fediverse_handle("dave@127.0.0.1"), None);
+        assert_eq!(parse_fediverse_handle("dave@2130706433"), None);
+        assert_eq!(parse_fediverse_handle("dave@[::1]"), None);
+    }
+
+    #[test]
+    fn test_build_subscribe_url() {
+        assert_eq!(
+            build_subscribe_url(
+                "https://podcastindex.social/authorize_interaction?uri={uri}",
+                "https://ap.podcastindex.org/podcasts?id=6594066",
+            ),
+            "https://podcastindex.social/authorize_interaction?uri=https%3A%2F%2Fap.podcastindex.org%2Fpodcasts%3Fid%3D6594066"
+        );
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(html_escape("<b>'Tom' & \"Jerry\"</b>"), "&lt;b&gt;&#39;Tom&#39; &amp; &quot;Jerry&quot;&lt;/b&gt;");
+    }
+
+    #[test]
+    fn test_paginate_by_date_first_page() {
+        let dates: Vec<u64> = (1..=5).collect();
+        let (page, has_older, has_newer) = paginate_by_date(dates, |date| *date, None, None, 2);
+        assert_eq!(page, vec![5, 4]);
+        assert!(has_older);
+        assert!(!has_newer);
+    }
+
+    #[test]
+    fn test_paginate_by_date_max_id() {
+        let dates: Vec<u64> = (1..=5).collect();
+        let (page, has_older, has_newer) = paginate_by_date(dates, |date| *date, Some(4), None, 2);
+        assert_eq!(page, vec![3, 2]);
+        assert!(has_older);
+        assert!(has_newer);
+
+        let dates: Vec<u64> = (1..=5).collect();
+        let (page, has_older, _) = paginate_by_date(dates, |date| *date, Some(2), None, 2);
+        assert_eq!(page, vec![1]);
+        assert!(!has_older);
+    }
+
+    #[test]
+    fn test_paginate_by_date_min_id() {
+        let dates: Vec<u64> = (1..=5).collect();
+        let (page, has_older, has_newer) = paginate_by_date(dates, |date| *date, None, Some(0), 2);
+        assert_eq!(page, vec![2, 1]);
+        assert!(!has_older);
+        assert!(has_newer);
+
+        let dates: Vec<u64> = (1..=5).collect();
+        let (page, has_older, has_newer) = paginate_by_date(dates, |date| *date, None, Some(3), 2);
+        assert_eq!(page, vec![5, 4]);
+        assert!(has_older);
+        assert!(!has_newer);
+    }
+
+    #[test]
+    fn test_paginate_by_date_same_second() {
+        let episodes: Vec<(u64, u64)> = vec![(300, 7), (200, 6), (200, 5), (200, 4), (100, 3)];
+        let (page, has_older, _) = paginate_by_date(episodes.clone(), |episode| *episode, None, None, 2);
+        assert_eq!(page, vec![(300, 7), (200, 6)]);
+        assert!(has_older);
+
+        let (page, has_older, has_newer) = paginate_by_date(episodes.clone(), |episode| *episode, Some((200, 6)), None, 2);
+        assert_eq!(page, vec![(200, 5), (200, 4)]);
+        assert!(has_older);
+        assert!(has_newer);
+
+        let (page, _, has_newer) = paginate_by_date(episodes, |episode| *episode, None, Some((200, 4)), 2);
+        assert_eq!(page, vec![(200, 6), (200, 5)]);
+        assert!(has_newer);
+    }
+
+    #[test]
+    fn test_parse_outbox_cursor() {
+        assert_eq!(parse_outbox_cursor("1700000000_42", 0), Some((1700000000, 42)));
+        assert_eq!(parse_outbox_cursor("1700000000", 0), Some((1700000000, 0)));
+        assert_eq!(parse_outbox_cursor("1700000000", u64::MAX), Some((1700000000, u64::MAX)));
+        assert_eq!(parse_outbox_cursor("1700000000_", 0), None);
+        assert_eq!(parse_outbox_cursor("soon", 0), None);
+    }
+
+    //##: Serves the sample feed, honoring `since` the way the index does, and keeps the params of each request
+    #[derive(Debug)]
+    struct RecordingClient {
+        inner: PodcastIndexFixtures,
+        requests: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PodcastIndexClient for RecordingClient {
+        async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String> {
+            self.requests.lock().unwrap().push(format!(
+                "{}?{}",
+                endpoint,
+                params.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<String>>().join("&")
+            ));
+            let body = self.inner.fetch(endpoint, params).await?;
+            let since = params.iter().find(|(name, _)| *name == "since").and_then(|(_, since)| since.parse::<u64>().ok());
+            let mut episodes: serde_json::Value = serde_json::from_str(&body).unwrap();
+            if let (Some(since), Some(items)) = (since, episodes["items"].as_array_mut()) {
+                items.retain(|item| item["datePublished"].as_u64().unwrap_or(0) >= since);
+            }
+            Ok(episodes.to_string())
+        }
+
+        async fn hub_rescan(&self, feed_id: &str) -> PIResult<String> {
+            self.inner.hub_rescan(feed_id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ap_get_outbox_episodes_window() {
+        let client = RecordingClient {
+            inner: PodcastIndexFixtures::new(concat!(env!("CARGO_MANIFEST_DIR"), "/sample_data/podcastindex")),
+            requests: std::sync::Mutex::new(Vec::new()),
+        };
+
+        //##: An older page only takes the episodes from the cursor on plus one more page
+        let max_id = Some((1700179200, 16795090001));
+        let episodes = ap_get_outbox_episodes(&client, "920666", max_id, None).await.unwrap();
+        assert_eq!(
+            *client.requests.lock().unwrap(),
+            vec![
+                "/episodes/byfeedid?id=920666&since=1700179200&max=1000".to_string(),
+                format!("/episodes/byfeedid?id=920666&max={}", AP_OUTBOX_PAGE_SIZE + 2),
+            ]
+        );
+        let (page, has_older, has_newer) = paginate_by_date(
+            episodes.items,
+            |episode| (episode.datePublished, episode.id),
+            max_id,
+            None,
+            AP_OUTBOX_PAGE_SIZE,
+        );
+        assert_eq!(page.iter().map(|episode| episode.id).collect::<Vec<u64>>(), vec![16660000001]);
+        assert!(!has_older);
+        assert!(has_newer);
+    }
+
+    #[test]
+    fn test_parse_webfinger_resource() {
+        let base_url = "https://ap.example.com";
+        let host = "ap.example.com";
+        assert_eq!(
+            parse_webfinger_resource("acct:920666@ap.example.com", base_url, host),
+            Some(PodcastLookup::FeedId("920666".to_string()))
+        );
+        assert_eq!(
+            parse_webfinger_resource("https://ap.example.com/podcasts?id=920666", base_url, host),
+            Some(PodcastLookup::FeedId("920666".to_string()))
+        );
+        assert_eq!(
+            parse_webfinger_resource("acct:917393E3-1B1E-5CEF-ACE4-EDAA54E1F810@ap.example.com", base_url, host),
+            Some(PodcastLookup::Guid("917393e3-1b1e-5cef-ace4-edaa54e1f810".to_string()))
+        );
+        assert_eq!(
+            parse_webfinger_resource("https://feeds.example.org/show.xml", base_url, host),
+            Some(PodcastLookup::FeedUrl("https://feeds.example.org/show.xml".to_string()))
+        );
+        assert_eq!(
+            parse_webfinger_resource("acct:Podcasting-2.0@ap.example.com", base_url, host),
+            Some(PodcastLookup::Slug("podcasting-2-0".to_string()))
+        );
+        assert_eq!(parse_webfinger_resource("acct:920666@other.example.com", base_url, host), None);
+        assert_eq!(parse_webfinger_resource("https://ap.example.com/podcasts?id=abc", base_url, host), None);
+        assert_eq!(parse_webfinger_resource("acct:@ap.example.com", base_url, host), None);
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Podcasting 2.0"), "podcasting-2-0");
+        assert_eq!(slugify("  The -- Daily! "), "the-daily");
+        assert_eq!(slugify("already-a-slug"), "already-a-slug");
+    }
+
+    #[test]
+    fn test_ap_collection_page_offset() {
+        assert_eq!(ap_collection_page_offset(1), Some(0));
+        assert_eq!(ap_collection_page_offset(3), Some(2 * AP_COLLECTION_PAGE_SIZE));
+        assert_eq!(ap_collection_page_offset(0), None);
+        assert_eq!(ap_collection_page_offset(u64::MAX), None);
+        assert_eq!(ap_collection_page_offset(i64::MAX as u64 / AP_COLLECTION_PAGE_SIZE + 2), None);
+    }
+
+    #[test]
+    fn test_html_escape_url() {
+        assert_eq!(html_escape_url("https://example.com/ep.mp3?a=1&b='2'"), "https://example.com/ep.mp3?a=1&amp;b=&#39;2&#39;");
+        assert_eq!(html_escape_url("http://example.com/"), "http://example.com/");
+        assert_eq!(html_escape_url("javascript:alert(1)"), "");
+        assert_eq!(html_escape_url("data:text/html,<b>x</b>"), "");
+        assert!(!is_web_url("JavaScript:alert(1)"));
+    }
+
+    #[test]
+    fn test_ap_build_in_reply_to_html() {
+        assert_eq!(
+            ap_build_in_reply_to_html("https://remote.example/users/alice/statuses/1"),
+            "<p>In reply to <a href='https://remote.example/users/alice/statuses/1'>https://remote.example/users/alice/statuses/1</a></p>"
+        );
+        assert_eq!(
+            ap_build_in_reply_to_html("javascript:alert('x')"),
+            "<p>In reply to javascript:alert(&#39;x&#39;)</p>"
+        );
+    }
+
+    #[test]
+    fn test_html_strip_tags() {
+        assert_eq!(html_strip_tags("<p>Show <b>notes</b></p>"), "Show notes");
+        assert_eq!(html_strip_tags("  plain text "), "plain text");
+        assert_eq!(html_strip_tags("1 > 0 <br/>done"), "1 > 0 done");
+    }
+
+    #[test]
+    fn test_media_type_is_activity_json() {
+        assert!(media_type_is_activity_json("application/activity+json"));
+        assert!(media_type_is_activity_json(
+            "application/ld+json; profile=\"https://www.w3.org/ns/activitystreams\""
+        ));
+        assert!(media_type_is_activity_json("text/html, Application/Activity+JSON;q=0.9"));
+        assert!(!media_type_is_activity_json("text/html,application/xhtml+xml,*/*;q=0.8"));
+        assert!(!media_type_is_activity_json("*/*"));
+    }
+
+    fn tracker_episodes(episodes: &[(&str, u64)]) -> Vec<PIItem> {
+        episodes.iter().map(|(guid, published)| {
+            serde_json::from_value(serde_json::json!({
+                "id": published,
+                "title": guid,
+                "link": "",
+                "description": "",
+                "guid": guid,
+                "datePublished": published,
+                "datePublishedPretty": "",
+                "enclosureUrl": "",
+                "enclosureType": "",
+                "image": "",
+                "feedImage": "",
+                "feedId": 920666,
+                "feedItunesId": null,
+                "socialInteract": null,
+                "transcripts": null
+            })).unwrap()
+        }).collect()
+    }
+
+    fn guids(episodes: &[&PIItem]) -> Vec<String> {
+        episodes.iter().map(|episode| episode.guid.clone()).collect()
+    }
+
+    #[test]
+    fn test_select_new_episodes() {
+        //##: Newest first, the way the index lists them
+        let items = tracker_episodes(&[("e5", 500), ("e4", 400), ("e3", 300), ("e2", 200), ("e1", 100)]);
+
+        //##: Three dropped since e2, announced oldest first
+        let new_episodes = select_new_episodes(&items, 200, "e2", &["e2".to_string()], 5, EpisodeBacklogPolicy::Recent);
+        assert_eq!(guids(&new_episodes.announce), vec!["e3", "e4", "e5"]);
+        assert_eq!(new_episodes.last_published, 500);
+
+        //##: Nothing new
+        let new_episodes = select_new_episodes(&items, 500, "e5", &[], 5, EpisodeBacklogPolicy::Recent);
+        assert!(new_episodes.announce.is_empty());
+        assert!(new_episodes.seen.is_empty());
+        assert_eq!(new_episodes.last_published, 500);
+
+        //##: Two episodes sharing a publish time, one already sent
+        let items = tracker_episodes(&[("b", 300), ("a", 300), ("z", 200)]);
+        let new_episodes = select_new_episodes(&items, 300, "a", &["a".to_string()], 5, EpisodeBacklogPolicy::Recent);
+        assert_eq!(guids(&new_episodes.announce), vec!["b"]);
+    }
+
+    #[test]
+    fn test_select_new_episodes_first_run() {
+        let items = tracker_episodes(&[("e3", 300), ("e2", 200), ("e1", 100)]);
+
+        //##: Never tracked, only the newest goes out
+        let new_episodes = select_new_episodes(&items, 0, "", &[], 5, EpisodeBacklogPolicy::Recent);
+        assert_eq!(guids(&new_episodes.announce), vec!["e3"]);
+        assert_eq!(new_episodes.seen.len(), 3);
+        assert_eq!(new_episodes.last_published, 300);
+
+        //##: Tracked before the high water mark existed
+        let new_episodes = select_new_episodes(&items, 0, "e1", &[], 5, EpisodeBacklogPolicy::Recent);
+        assert_eq!(guids(&new_episodes.announce), vec!["e2", "e3"]);
+    }
+
+    #[test]
+    fn test_select_new_episodes_backlog() {
+        let items = tracker_episodes(&[("e5", 500), ("e4", 400), ("e3", 300), ("e2", 200), ("e1", 100)]);
+
+        let new_episodes = select_new_episodes(&items, 100, "e1", &[], 2, EpisodeBacklogPolicy::Recent);
+        assert_eq!(guids(&new_episodes.announce), vec!["e4", "e5"]);
+        assert_eq!(guids(&new_episodes.seen), vec!["e2", "e3", "e4", "e5"]);
+
+        let new_episodes = select_new_episodes(&items, 100, "e1", &[], 2, EpisodeBacklogPolicy::Latest);
+        assert_eq!(guids(&new_episodes.announce), vec!["e5"]);
+
+        let new_episodes = select_new_episodes(&items, 100, "e1", &[], 2, EpisodeBacklogPolicy::Skip);
+        assert!(new_episodes.announce.is_empty());
+        assert_eq!(new_episodes.seen.len(), 4);
+        assert_eq!(new_episodes.last_published, 500);
+    }
+
+    #[test]
+    fn test_ap_group_recent_episodes() {
+        let mut items = tracker_episodes(&[("e3", 300), ("e2", 200), ("e1", 100)]);
+        items[1].feedId = 75075;
+
+        let episodes_by_feed = ap_group_recent_episodes(items, 150).unwrap();
+        assert_eq!(episodes_by_feed.len(), 2);
+        assert_eq!(episodes_by_feed[&920666].len(), 2);
+        assert_eq!(episodes_by_feed[&75075][0].guid, "e2");
+
+        //##: Doesn't reach back far enough
+        let items = tracker_episodes(&[("e3", 300), ("e2", 200)]);
+        assert!(ap_group_recent_episodes(items, 150).is_none());
+        let items = tracker_episodes(&[("e3", 300)]);
+        assert!(ap_group_recent_episodes(items, 0).is_none());
+    }
+
+    #[test]
+    fn test_ap_check_remote_key_owner() {
+        let key_id = "https://mastodon.social/users/alice#main-key";
+        let alice = "https://mastodon.social/users/alice";
+        assert!(ap_check_remote_key_owner(key_id, alice, key_id, alice).is_ok());
+
+        //##: An actor on another server claiming its key belongs to alice
+        let evil_key_id = "https://evil.example/users/mallory#main-key";
+        let mallory = "https://evil.example/users/mallory";
+        assert!(ap_check_remote_key_owner(evil_key_id, mallory, evil_key_id, alice).is_err());
+        assert!(ap_check_remote_key_owner(evil_key_id, alice, evil_key_id, alice).is_err());
+
+        //##: The document has to publish the key that was asked for
+        assert!(ap_check_remote_key_owner(key_id, alice, "https://mastodon.social/users/alice#other-key", alice).is_err());
+    }
 }
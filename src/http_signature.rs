@@ -1,4 +1,6 @@
-use chrono::Utc;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
 use http::Method;
 use sha2::{Digest, Sha256};
 
@@ -6,13 +8,18 @@ use crate::{
     base64,
     crypto_rsa::{
         create_rsa_sha256_signature,
+        verify_rsa_sha256_signature,
         RsaError,
         RsaPrivateKey,
+        RsaPublicKey,
     },
 };
 
 const HTTP_SIGNATURE_ALGORITHM: &str = "rsa-sha256";
+const HTTP_SIGNATURE_ALGORITHM_HS2019: &str = "hs2019";
 const HTTP_SIGNATURE_DATE_FORMAT: &str = "%a, %d %b %Y %T GMT";
+const HTTP_SIGNATURE_MAX_AGE_MINUTES: i64 = 5;
+const HTTP_SIGNATURE_POST_HEADERS: [&str; 4] = ["(request-target)", "host", "date", "digest"];
 
 pub struct HttpSignatureHeaders {
     pub host: String,
@@ -30,10 +37,40 @@ pub enum HttpSignatureError {
     SigningError(#[from] RsaError),
 }
 
+pub struct HttpSignatureData {
+    pub key_id: String,
+    pub message: String,
+    pub signature: Vec<u8>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum HttpSignatureVerificationError {
+    #[error("missing signature header")]
+    NoSignature,
+
+    #[error("{0}")]
+    HeaderError(&'static str),
+
+    #[error("{0}")]
+    ParseError(&'static str),
+
+    #[error("invalid encoding")]
+    InvalidEncoding(#[from] base64::DecodeError),
+
+    #[error("signature expired")]
+    Expired,
+
+    #[error("digest mismatch")]
+    InvalidDigest,
+
+    #[error("invalid signature")]
+    InvalidSignature,
+}
+
 fn get_message_digest(message: &str) -> String {
     let digest = Sha256::digest(message.as_bytes());
-    let digest_b64 = base64::encode(digest);
-    digest_b64
+    
+    base64::encode(digest)
 }
 
 /// Creates HTTP signature according to the old HTTP Signatures Spec:
@@ -100,3 +137,274 @@ pub fn create_http_signature(
     };
     Ok(headers)
 }
+
+
+/// Parses the Signature header of an incoming request and rebuilds the signing string
+/// from the headers it covers.
+/// https://datatracker.ietf.org/doc/html/draft-cavage-http-signatures
+pub fn parse_http_signature(
+    request_method: &hyper::Method,
+    request_uri: &hyper::Uri,
+    request_headers: &hyper::HeaderMap,
+) -> Result<HttpSignatureData, HttpSignatureVerificationError> {
+    let signature_header = request_headers.get("signature")
+        .ok_or(HttpSignatureVerificationError::NoSignature)?
+        .to_str()
+        .map_err(|_| HttpSignatureVerificationError::HeaderError("invalid signature header"))?;
+
+    let mut signature_parameters = HashMap::new();
+    for item in signature_header.split(',') {
+        let (name, value) = item.trim().split_once('=')
+            .ok_or(HttpSignatureVerificationError::ParseError("invalid signature parameter"))?;
+        signature_parameters.insert(name.trim(), value.trim().trim_matches('"'));
+    }
+
+    let key_id = signature_parameters.get("keyId")
+        .ok_or(HttpSignatureVerificationError::ParseError("keyId parameter is missing"))?
+        .to_string();
+    if let Some(algorithm) = signature_parameters.get("algorithm") {
+        if *algorithm != HTTP_SIGNATURE_ALGORITHM && *algorithm != HTTP_SIGNATURE_ALGORITHM_HS2019 {
+            return Err(HttpSignatureVerificationError::ParseError("unsupported algorithm"));
+        }
+    }
+    let headers_parameter = signature_parameters.get("headers")
+        .copied()
+        .unwrap_or("date");
+    let signature_parameter = signature_parameters.get("signature")
+        .ok_or(HttpSignatureVerificationError::ParseError("signature parameter is missing"))?;
+
+    //A signature has to cover the date, and on a POST also where it was sent and the digest of the body.
+    //Otherwise it could be lifted off one request and put on another
+    let signed_headers = headers_parameter.split_whitespace()
+        .map(|header| header.to_lowercase())
+        .collect::<Vec<String>>();
+    if !signed_headers.iter().any(|header| header == "date") {
+        return Err(HttpSignatureVerificationError::ParseError("date is not signed"));
+    }
+    if *request_method == hyper::Method::POST {
+        for required_header in HTTP_SIGNATURE_POST_HEADERS {
+            if !signed_headers.iter().any(|header| header == required_header) {
+                return Err(HttpSignatureVerificationError::ParseError(
+                    "signature must cover (request-target), host, date and digest",
+                ));
+            }
+        }
+    }
+
+    let mut message_parts = vec![];
+    for header in headers_parameter.split_whitespace() {
+        let header = header.to_lowercase();
+        let message_part = match header.as_str() {
+            "(request-target)" => {
+                let path_and_query = request_uri.path_and_query()
+                    .map(|value| value.as_str())
+                    .unwrap_or("/");
+                format!(
+                    "{}: {} {}",
+                    header,
+                    request_method.as_str().to_lowercase(),
+                    path_and_query,
+                )
+            }
+            "(created)" | "(expires)" => {
+                let parameter_name = header.trim_matches(|c| c == '(' || c == ')');
+                let value = signature_parameters.get(parameter_name)
+                    .ok_or(HttpSignatureVerificationError::ParseError("pseudo-header parameter is missing"))?;
+                format!("{}: {}", header, value)
+            }
+            _ => {
+                let values = request_headers.get_all(header.as_str())
+                    .iter()
+                    .map(|value| value.to_str())
+                    .collect::<Result<Vec<&str>, _>>()
+                    .map_err(|_| HttpSignatureVerificationError::HeaderError("invalid header value"))?;
+                if values.is_empty() {
+                    return Err(HttpSignatureVerificationError::HeaderError("signed header is missing"));
+                }
+                format!("{}: {}", header, values.join(", "))
+            }
+        };
+        message_parts.push(message_part);
+    }
+
+    //The date header must be fresh, otherwise a captured request could be replayed
+    let date = request_headers.get("date")
+        .ok_or(HttpSignatureVerificationError::HeaderError("missing date header"))?
+        .to_str()
+        .map_err(|_| HttpSignatureVerificationError::HeaderError("invalid date header"))?;
+    let signed_at = DateTime::parse_from_rfc2822(date)
+        .map_err(|_| HttpSignatureVerificationError::HeaderError("invalid date header"))?
+        .with_timezone(&Utc);
+    let max_age = Duration::minutes(HTTP_SIGNATURE_MAX_AGE_MINUTES);
+    if signed_at < Utc::now() - max_age || signed_at > Utc::now() + max_age {
+        return Err(HttpSignatureVerificationError::Expired);
+    }
+
+    let signature = base64::decode(signature_parameter)?;
+    let signature_data = HttpSignatureData {
+        key_id,
+        message: message_parts.join("\n"),
+        signature,
+    };
+    Ok(signature_data)
+}
+
+/// Checks the Digest header of an incoming request against the SHA-256 hash of its body.
+pub fn verify_digest_header(
+    request_headers: &hyper::HeaderMap,
+    request_body: &str,
+) -> Result<(), HttpSignatureVerificationError> {
+    let digest_header = request_headers.get("digest")
+        .ok_or(HttpSignatureVerificationError::HeaderError("missing digest header"))?
+        .to_str()
+        .map_err(|_| HttpSignatureVerificationError::HeaderError("invalid digest header"))?;
+    let expected_digest = get_message_digest(request_body);
+    for digest in digest_header.split(',') {
+        if let Some((algorithm, value)) = digest.trim().split_once('=') {
+            if algorithm.eq_ignore_ascii_case("SHA-256") {
+                if value == expected_digest {
+                    return Ok(());
+                }
+                return Err(HttpSignatureVerificationError::InvalidDigest);
+            }
+        }
+    }
+    Err(HttpSignatureVerificationError::HeaderError("no SHA-256 digest given"))
+}
+
+pub fn verify_http_signature(
+    signature_data: &HttpSignatureData,
+    signer_key: &RsaPublicKey,
+) -> Result<(), HttpSignatureVerificationError> {
+    let is_valid_signature = verify_rsa_sha256_signature(
+        signer_key,
+        &signature_data.message,
+        &signature_data.signature,
+    );
+    if !is_valid_signature {
+        return Err(HttpSignatureVerificationError::InvalidSignature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto_rsa::generate_weak_rsa_key;
+    use super::*;
+
+    fn build_signed_request(
+        request_url: &str,
+        request_body: &str,
+        signer_key: &RsaPrivateKey,
+    ) -> (hyper::Uri, hyper::HeaderMap) {
+        let signature_headers = create_http_signature(
+            Method::POST,
+            request_url,
+            request_body,
+            signer_key,
+            "https://example.org/users/test#main-key",
+        ).unwrap();
+        let mut request_headers = hyper::HeaderMap::new();
+        request_headers.insert("host", signature_headers.host.parse().unwrap());
+        request_headers.insert("date", signature_headers.date.parse().unwrap());
+        request_headers.insert("digest", signature_headers.digest.unwrap().parse().unwrap());
+        request_headers.insert("signature", signature_headers.signature.parse().unwrap());
+        (request_url.parse().unwrap(), request_headers)
+    }
+
+    #[test]
+    fn test_create_and_verify_http_signature() {
+        let private_key = generate_weak_rsa_key().unwrap();
+        let request_body = "{}";
+        let (request_uri, request_headers) = build_signed_request(
+            "https://example.org/inbox",
+            request_body,
+            &private_key,
+        );
+
+        let signature_data = parse_http_signature(
+            &hyper::Method::POST,
+            &request_uri,
+            &request_headers,
+        ).unwrap();
+        assert_eq!(signature_data.key_id, "https://example.org/users/test#main-key");
+
+        let public_key = RsaPublicKey::from(&private_key);
+        assert!(verify_digest_header(&request_headers, request_body).is_ok());
+        assert!(verify_http_signature(&signature_data, &public_key).is_ok());
+    }
+
+    #[test]
+    fn test_verify_http_signature_wrong_method() {
+        let private_key = generate_weak_rsa_key().unwrap();
+        let (request_uri, request_headers) = build_signed_request(
+            "https://example.org/inbox",
+            "{}",
+            &private_key,
+        );
+
+        let signature_data = parse_http_signature(
+            &hyper::Method::GET,
+            &request_uri,
+            &request_headers,
+        ).unwrap();
+        let public_key = RsaPublicKey::from(&private_key);
+        assert!(verify_http_signature(&signature_data, &public_key).is_err());
+    }
+
+    #[test]
+    fn test_verify_digest_header_body_changed() {
+        let private_key = generate_weak_rsa_key().unwrap();
+        let (_request_uri, request_headers) = build_signed_request(
+            "https://example.org/inbox",
+            "{}",
+            &private_key,
+        );
+
+        let result = verify_digest_header(&request_headers, "{\"type\":\"Follow\"}");
+        assert!(matches!(result, Err(HttpSignatureVerificationError::InvalidDigest)));
+    }
+
+    #[test]
+    fn test_parse_http_signature_unsigned() {
+        let request_uri: hyper::Uri = "https://example.org/inbox".parse().unwrap();
+        let request_headers = hyper::HeaderMap::new();
+        let result = parse_http_signature(&hyper::Method::POST, &request_uri, &request_headers);
+        assert!(matches!(result, Err(HttpSignatureVerificationError::NoSignature)));
+    }
+
+    #[test]
+    fn test_parse_http_signature_post_must_cover_digest() {
+        let private_key = generate_weak_rsa_key().unwrap();
+        let (request_uri, mut request_headers) = build_signed_request(
+            "https://example.org/inbox",
+            "{}",
+            &private_key,
+        );
+        let signature_header = request_headers.get("signature").unwrap().to_str().unwrap()
+            .replace("headers=\"(request-target) host date digest\"", "headers=\"(request-target) host date\"");
+        request_headers.insert("signature", signature_header.parse().unwrap());
+
+        let result = parse_http_signature(&hyper::Method::POST, &request_uri, &request_headers);
+        assert!(matches!(result, Err(HttpSignatureVerificationError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_parse_http_signature_date() {
+        let private_key = generate_weak_rsa_key().unwrap();
+        let (request_uri, mut request_headers) = build_signed_request(
+            "https://example.org/inbox",
+            "{}",
+            &private_key,
+        );
+
+        let stale_date = (Utc::now() - Duration::hours(1)).format(HTTP_SIGNATURE_DATE_FORMAT).to_string();
+        request_headers.insert("date", stale_date.parse().unwrap());
+        let result = parse_http_signature(&hyper::Method::POST, &request_uri, &request_headers);
+        assert!(matches!(result, Err(HttpSignatureVerificationError::Expired)));
+
+        request_headers.remove("date");
+        let result = parse_http_signature(&hyper::Method::POST, &request_uri, &request_headers);
+        assert!(matches!(result, Err(HttpSignatureVerificationError::HeaderError(_))));
+    }
+}
@@ -1,32 +1,49 @@
 use hyper::{body::to_bytes, service::{make_service_fn, service_fn}, Body, Request, Server};
 use route_recognizer::Params;
 use router::Router;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use hyper::server::conn::AddrStream;
 use std::env;
+use std::path::PathBuf;
 use std::string::ToString;
-use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use serde::{Deserialize, Serialize};
 use crate::handler::{
-    api_block_get_episodes,
-    ap_block_send_episode_note,
-    ap_block_send_live_note,
-    PIEpisodes,
+    ap_group_recent_episodes,
+    ap_new_episodes,
+    ap_send_episode_note,
+    ap_send_live_note,
+};
+use crate::podping::{
+    PodpingCursor,
+    PodpingError,
+    PodpingSourceConfig,
+};
+use crate::podcastindex::{
+    PodcastIndexApi,
+    PodcastIndexCache,
+    PodcastIndexClient,
+    PodcastIndexFixtures,
+    PIItem,
     PILiveItems,
-    api_block_get_live_items
 };
-use url::Url;
-use tungstenite::{connect};
+use dbif::{ActorRecord, SeenEpisodeRecord};
 
 //Globals ----------------------------------------------------------------------------------------------------
 mod handler;
+mod podcastindex;
+mod podping;
 mod router;
 mod http_signature;
 mod crypto_rsa;
 mod base64;
 
 const LOOP_TIMER_MILLISECONDS: u64 = 60000;
+const EPISODE_SWEEP_SECONDS: u64 = 21600;
+const RECENT_EPISODE_FETCH: u64 = 1000;
+const PODPING_EPISODE_CHECK_DELAYS_SECONDS: [u64; 2] = [20, 90];
+static EPISODE_CHECKS_RUNNING: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+static PODPING_EPISODE_CHECKS_PENDING: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+const DELIVERY_LOOP_TIMER_MILLISECONDS: u64 = 10000;
 const AP_DATABASE_FILE: &str = "database.db";
 const USER_AGENT_PARAM: &str = concat!("PodcastIndexAPBridge_v", env!("CARGO_PKG_VERSION"));
 //const USER_AGENT_HEADER: &str = concat!("Podcast Index AP-Bridge/v", env!("CARGO_PKG_VERSION"));
@@ -47,49 +64,11 @@ pub struct Context {
     pub state: AppState,
     pub req: Request<Body>,
     pub params: Params,
-    pub pi_auth: PIAuth,
+    pub pi_client: Arc<dyn PodcastIndexClient>,
     pub version: String,
     body_bytes: Option<hyper::body::Bytes>,
 }
 
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct SocketPayload {
-    pub a: String,
-    pub n: u64,
-    pub o: u64,
-    pub p: Vec<PodpingPayload>,
-    pub t: String,
-    pub v: u64,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct PodpingPayload {
-    pub a: String,
-    pub i: String,
-    pub p: Podping,
-    pub t: String,
-}
-
-#[allow(non_snake_case)]
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Podping {
-    pub iris: Vec<String>,
-    pub medium: Option<String>,
-    pub reason: String,
-    pub sessionId: Option<String>,
-    pub timestampNs: Option<u64>,
-    pub version: Option<String>,
-}
-
-#[allow(non_snake_case)]
-#[derive(Clone, Debug)]
-pub struct PIAuth {
-    pub key: String,
-    pub secret: String,
-}
-
 
 //Functions --------------------------------------------------------------------------------------------------
 #[tokio::main]
@@ -103,23 +82,105 @@ async fn main() {
     let arg_port = &args[1];
     //let arg_chatid = &args[2];
 
-    let env_pi_api_key;
-    match std::env::var("PI_API_KEY") {
-        Ok(key) => {
-            env_pi_api_key = key;
+    //##: Talk to the Podcast Index, or to a directory of canned api responses when running offline
+    let mut pi_client: Arc<dyn PodcastIndexClient>;
+    match std::env::var("PI_API_FIXTURES") {
+        Ok(fixture_dir) => {
+            println!("Podcast Index fixtures: {}", fixture_dir);
+            pi_client = Arc::new(PodcastIndexFixtures::new(&fixture_dir));
         }
         Err(_) => {
-            eprintln!("PI_API_KEY environment variable not set.");
+            let env_pi_api_key = match std::env::var("PI_API_KEY") {
+                Ok(key) => {
+                    key
+                }
+                Err(_) => {
+                    eprintln!("PI_API_KEY environment variable not set.");
+                    std::process::exit(1);
+                }
+            };
+            let env_pi_api_secret = match std::env::var("PI_API_SECRET") {
+                Ok(secret) => {
+                    secret
+                }
+                Err(_) => {
+                    eprintln!("PI_API_SECRET environment variable not set.");
+                    std::process::exit(1);
+                }
+            };
+            let api_url = std::env::var("PI_API_URL").unwrap_or(podcastindex::PI_DEFAULT_API_URL.to_string());
+            let mut api_timeout = podcastindex::PI_DEFAULT_TIMEOUT_SECONDS;
+            if let Ok(timeout_seconds) = std::env::var("PI_API_TIMEOUT_SECONDS") {
+                match timeout_seconds.parse::<u64>() {
+                    Ok(seconds) => {
+                        api_timeout = seconds;
+                    }
+                    Err(_) => {
+                        eprintln!("PI_API_TIMEOUT_SECONDS must be a whole number of seconds.");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            match PodcastIndexApi::new(
+                &env_pi_api_key,
+                &env_pi_api_secret,
+                &api_url,
+                Duration::from_secs(api_timeout),
+            ) {
+                Ok(api) => {
+                    println!("Podcast Index api: {}", api_url);
+                    pi_client = Arc::new(api);
+                }
+                Err(e) => {
+                    eprintln!("Could not set up the Podcast Index api client: [{}]", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    //##: Public base url of this bridge, if not the default
+    if let Ok(base_url) = std::env::var("AP_BASE_URL") {
+        if let Err(e) = handler::set_ap_base_url(&base_url) {
+            eprintln!("AP_BASE_URL is not a valid url: [{}]", e);
             std::process::exit(1);
         }
     }
-    let env_pi_api_secret;
-    match std::env::var("PI_API_SECRET") {
-        Ok(secret) => {
-            env_pi_api_secret = secret;
+    println!("Base url: {}", handler::ap_base_url());
+
+    //##: Hide the member list of followers collections, only giving out the count
+    if let Ok(hide_followers) = std::env::var("AP_HIDE_FOLLOWERS") {
+        handler::set_ap_hide_followers(hide_followers == "1" || hide_followers.to_lowercase() == "true");
+    }
+
+    //##: How long to keep retrying an outbound delivery before giving up on it
+    if let Ok(horizon_hours) = std::env::var("AP_DELIVERY_HORIZON_HOURS") {
+        match horizon_hours.parse::<u64>() {
+            Ok(hours) => {
+                handler::set_ap_delivery_horizon(hours);
+            }
+            Err(_) => {
+                eprintln!("AP_DELIVERY_HORIZON_HOURS must be a whole number of hours.");
+                std::process::exit(1);
+            }
         }
-        Err(_) => {
-            eprintln!("PI_API_SECRET environment variable not set.");
+    }
+
+    //##: How many new episodes of one podcast to announce at a time, and what to do when a lot more show up at once
+    if let Ok(max_new_episodes) = std::env::var("AP_TRACKER_MAX_NEW_EPISODES") {
+        match max_new_episodes.parse::<u64>() {
+            Ok(max_new) => {
+                handler::set_ap_tracker_max_new_episodes(max_new);
+            }
+            Err(_) => {
+                eprintln!("AP_TRACKER_MAX_NEW_EPISODES must be a whole number.");
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Ok(backlog_policy) = std::env::var("AP_TRACKER_BACKLOG_POLICY") {
+        if let Err(e) = handler::set_ap_episode_backlog_policy(&backlog_policy) {
+            eprintln!("AP_TRACKER_BACKLOG_POLICY must be recent, latest or skip: [{}]", e);
             std::process::exit(1);
         }
     }
@@ -129,43 +190,95 @@ async fn main() {
     if dbif::create_database(&AP_DATABASE_FILE.to_string()).is_err() {
         eprintln!("Error initializing the database file.");
     }
+    if let Err(e) = handler::ap_init_instance_actor() {
+        eprintln!("Error setting up the instance actor keys: [{}]", e);
+    }
+
+    //##: Keep index responses around for a while so repeat lookups don't all go back out to the api
+    let mut cache_max_entries = podcastindex::PI_DEFAULT_CACHE_MAX_ENTRIES;
+    if let Ok(max_entries) = std::env::var("PI_CACHE_MAX_ENTRIES") {
+        match max_entries.parse::<usize>() {
+            Ok(entries) => {
+                cache_max_entries = entries;
+            }
+            Err(_) => {
+                eprintln!("PI_CACHE_MAX_ENTRIES must be a whole number.");
+                std::process::exit(1);
+            }
+        }
+    }
+    if cache_max_entries > 0 {
+        let mut cache_database = None;
+        if let Ok(persist) = std::env::var("PI_CACHE_PERSIST") {
+            if persist == "1" || persist.to_lowercase() == "true" {
+                cache_database = Some(AP_DATABASE_FILE.to_string());
+            }
+        }
+        println!("Podcast Index cache: {} entries, persisted: {}", cache_max_entries, cache_database.is_some());
+        pi_client = Arc::new(PodcastIndexCache::new(pi_client, cache_max_entries, cache_database));
+    }
+
+    //##: Start tasks to track podcast new episodes and also podping
+    let tracker_pi_client = pi_client.clone();
+    tokio::spawn(async move {
+        loop {
+            let task_handle = tokio::spawn(episode_tracker(tracker_pi_client.clone()));
+            match task_handle.await {
+                Ok(_) => {
+                    println!("*****Episode Tracker Task Exited*****");
+                }
+                Err(e) => {
+                    eprintln!("*****Episode Tracker Task Exited*****:  [{:#?}]", e);
+                }
+            }
+        }
+    });
+
+    //##: Read podpings from the LiveWire socket, some other socket, or a file of recorded socket messages
+    let podping_config;
+    match (std::env::var("PODPING_REPLAY"), std::env::var("PODPING_SOCKET_URL")) {
+        (Ok(replay_path), _) => {
+            if replay_path == "-" {
+                podping_config = PodpingSourceConfig::Replay(None);
+            } else {
+                podping_config = PodpingSourceConfig::Replay(Some(PathBuf::from(replay_path)));
+            }
+        }
+        (Err(_), Ok(socket_url)) => {
+            podping_config = PodpingSourceConfig::Websocket(socket_url);
+        }
+        (Err(_), Err(_)) => {
+            podping_config = PodpingSourceConfig::Websocket(
+                format!("{}?agent={}", podping::PODPING_DEFAULT_SOCKET_URL, USER_AGENT_PARAM)
+            );
+        }
+    }
+    println!("Podping source: {:?}", podping_config);
 
-    //##: Start threads to track podcast new episodes and also podping
-    let env_tracker_pi_api_key = env_pi_api_key.clone();
-    let env_tracker_pi_api_secret = env_pi_api_secret.clone();
-    thread::spawn(move || {
+    let live_pi_client = pi_client.clone();
+    tokio::spawn(async move {
         loop {
-            let key = env_tracker_pi_api_key.clone();
-            let secret = env_tracker_pi_api_secret.clone();
-            let thread_handle = thread::spawn(move || {
-                episode_tracker(key, secret);
-            });
-            match thread_handle.join() {
+            let task_handle = tokio::spawn(live_item_tracker(live_pi_client.clone(), podping_config.clone()));
+            match task_handle.await {
                 Ok(_) => {
-                    println!("*****Episode Tracker Thread Exited*****");
+                    println!("*****Live Tracker Task Exited*****");
                 }
                 Err(e) => {
-                    eprintln!("*****Episode Tracker Thread Exited*****:  [{:#?}]", e);
+                    eprintln!("*****Live Tracker Task Exited*****:  [{:#?}]", e);
                 }
             }
         }
     });
-    
-    let env_live_pi_api_key = env_pi_api_key.clone();
-    let env_live_pi_api_secret = env_pi_api_secret.clone();
-    thread::spawn(move || {
+
+    tokio::spawn(async move {
         loop {
-            let key = env_live_pi_api_key.clone();
-            let secret = env_live_pi_api_secret.clone();
-            let thread_handle = thread::spawn(move || {
-                live_item_tracker(key, secret);
-            });
-            match thread_handle.join() {
+            let task_handle = tokio::spawn(delivery_worker());
+            match task_handle.await {
                 Ok(_) => {
-                    println!("*****Live Tracker Thread Exited*****");
+                    println!("*****Delivery Worker Task Exited*****");
                 }
                 Err(e) => {
-                    eprintln!("*****Live Tracker Thread Exited*****:  [{:#?}]", e);
+                    eprintln!("*****Delivery Worker Task Exited*****:  [{:#?}]", e);
                 }
             }
         }
@@ -183,9 +296,18 @@ async fn main() {
     router.post("/outbox", Box::new(handler::outbox)); //##: User outbox IN
     router.get("/featured", Box::new(handler::featured)); //##: Featured posts
     router.get("/episodes", Box::new(handler::episodes)); //##: Statuses
+    router.get("/notes", Box::new(handler::notes)); //##: Notes the bridge authored
+    router.get("/replies", Box::new(handler::replies)); //##: Status replies
+    router.get("/likes", Box::new(handler::likes)); //##: Status likes
+    router.get("/shares", Box::new(handler::shares)); //##: Status shares
     router.get("/contexts", Box::new(handler::contexts)); //##: Contexts
     router.get("/followers", Box::new(handler::followers)); //##: Followers
+    router.get("/following", Box::new(handler::following)); //##: Following, from the podroll
     router.get("/.well-known/webfinger", Box::new(handler::webfinger)); //##: Webfinger
+    router.get("/.well-known/nodeinfo", Box::new(handler::nodeinfo_links)); //##: NodeInfo discovery
+    router.get("/nodeinfo/2.1", Box::new(handler::nodeinfo)); //##: NodeInfo
+    router.get("/.well-known/host-meta", Box::new(handler::host_meta)); //##: Host meta
+    router.get("/ostatus_subscribe", Box::new(handler::ostatus_subscribe)); //##: Remote follow
 
     let shared_router = Arc::new(router);
     let new_service = make_service_fn(move |conn: &AddrStream| {
@@ -195,10 +317,7 @@ async fn main() {
         };
 
         let router_capture = shared_router.clone();
-        let pi_auth = PIAuth {
-            key: env_pi_api_key.clone(),
-            secret: env_pi_api_secret.clone(),
-        };
+        let service_pi_client = pi_client.clone();
         let main_version = version.to_string();
         async {
             Ok::<_, Error>(service_fn(move |req| {
@@ -206,7 +325,7 @@ async fn main() {
                     router_capture.clone(),
                     req,
                     app_state.clone(),
-                    pi_auth.clone(),
+                    service_pi_client.clone(),
                     main_version.clone(),
                 )
             }))
@@ -225,7 +344,7 @@ async fn route(
     router: Arc<Router>,
     req: Request<hyper::Body>,
     app_state: AppState,
-    pi_auth: PIAuth,
+    pi_client: Arc<dyn PodcastIndexClient>,
     version: String
 ) -> Result<Response, Error> {
     let found_handler = router.route(req.uri().path(), req.method());
@@ -235,7 +354,7 @@ async fn route(
             app_state,
             req,
             found_handler.params,
-            pi_auth,
+            pi_client,
             version
         ))
         .await;
@@ -247,14 +366,14 @@ impl Context {
         state: AppState,
         req: Request<Body>,
         params: Params,
-        pi_auth: PIAuth,
+        pi_client: Arc<dyn PodcastIndexClient>,
         version: String,
     ) -> Context {
         Context {
             state,
             req,
             params,
-            pi_auth,
+            pi_client,
             version,
             body_bytes: None,
         }
@@ -269,208 +388,396 @@ impl Context {
                 self.body_bytes.as_ref().expect("body_bytes was set above")
             }
         };
-        Ok(serde_json::from_slice(&body_bytes)?)
+        Ok(serde_json::from_slice(body_bytes)?)
     }
 }
 
-fn episode_tracker(api_key: String, api_secret: String) {
+async fn delivery_worker() {
     loop {
-        thread::sleep(Duration::from_millis(LOOP_TIMER_MILLISECONDS));
+        tokio::time::sleep(Duration::from_millis(DELIVERY_LOOP_TIMER_MILLISECONDS)).await;
+
+        match handler::ap_process_deliveries().await {
+            Ok(delivered) => {
+                if delivered > 0 {
+                    println!("DELIVERY: Sent [{}] queued activities.", delivered);
+                }
+            }
+            Err(e) => {
+                eprintln!("DELIVERY: Error processing the delivery queue: [{:#?}]", e);
+            }
+        }
+    }
+}
+
+//##: Podpings get most new episodes out quickly.  Behind that, every minute one call for the index's most recent
+//##: episodes picks up the rest for whichever of them are ours.  Podcasts are only looked up one by one when that
+//##: batch doesn't reach back to the last run, when a podcast has never been checked, or every few hours as a sweep
+async fn episode_tracker(pi_client: Arc<dyn PodcastIndexClient>) {
+    let mut last_run_time = 0;
+    let mut last_sweep_time = 0;
+    loop {
+        tokio::time::sleep(Duration::from_millis(LOOP_TIMER_MILLISECONDS)).await;
 
         let start_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs();
 
         println!("TRACKER: Polling podcast data.");
 
-        let actors;
-        match dbif::get_actors_from_db(&AP_DATABASE_FILE.to_string()) {
+        let mut recent_episodes = None;
+        if let Ok(recent_data) = pi_client.get_recent_episodes(RECENT_EPISODE_FETCH).await {
+            recent_episodes = ap_group_recent_episodes(recent_data.items, last_run_time);
+        }
+        let mut sweep = false;
+        if recent_episodes.is_none() || start_time >= last_sweep_time + EPISODE_SWEEP_SECONDS {
+            println!("TRACKER: Sweeping every podcast.");
+            sweep = true;
+        }
+        let mut recent_episodes = recent_episodes.unwrap_or_default();
+
+        let actors = match dbif::get_actors_from_db(&AP_DATABASE_FILE.to_string()) {
             Ok(actor_list) => {
-                actors = actor_list;
+                actor_list
             }
             Err(e) => {
                 eprintln!("  Error getting actors from the database: [{:#?}]", e);
                 continue;
             }
-        }
+        };
 
         let mut actor_count = 0;
+        let mut api_call_count = 0;
         for actor in actors {
             //##: Skip instance actor
             if actor.pcid == 0 {
                 continue;
             }
 
-            match dbif::get_followers_from_db(&AP_DATABASE_FILE.to_string(), actor.pcid) {
-                Ok(followers) => {
-                    if followers.len() > 0 {
-                        actor_count += 1;
-                    }
+            if dbif::get_follower_count_from_db(&AP_DATABASE_FILE.to_string(), actor.pcid).unwrap_or(0) > 0 {
+                actor_count += 1;
+            }
 
-                    //##: Lookup API of podcast
-                    println!("  Podcast API Call - [{}]", actor.pcid);
-                    match api_block_get_episodes(
-                        &api_key,
-                        &api_secret,
-                        &actor.pcid.to_string()
-                    ) {
-                        Ok(response_body) => {
-                            match serde_json::from_str(response_body.as_str()) {
-                                Ok(data) => {
-                                    let podcast_data: PIEpisodes = data;
-                                    //##: TODO Get this code out of this deep level of nesting
-                                    let latest_episode = podcast_data.items.get(0);
-                                    if latest_episode.is_some() {
-                                        let latest_episode_details = latest_episode.unwrap();
-                                        if actor.last_episode_guid != latest_episode_details.guid {
-                                            let mut shared_inboxes_called = Vec::new();
-                                            for follower in followers {
-                                                if !shared_inboxes_called.contains(&follower.shared_inbox) {
-                                                    let _ = ap_block_send_episode_note(
-                                                        actor.pcid,
-                                                        latest_episode_details,
-                                                        follower.shared_inbox.clone(),
-                                                        false,
-                                                        None
-                                                    );
-                                                    shared_inboxes_called.push(follower.shared_inbox.clone());
-                                                }
-                                            }
-
-                                            let _ = dbif::update_actor_last_episode_guid_in_db(
-                                                &AP_DATABASE_FILE.to_string(),
-                                                actor.pcid,
-                                                latest_episode_details.guid.clone(),
-                                            );
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    //##: TODO - refactor this deep nesting
-                                    eprintln!("  API response prep error: [{:#?}] actor guid: [{}].\n", e, actor.pcid);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            //##: TODO - refactor this deep nesting
-                            eprintln!("  PI API call error: [{:#?}].\n", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    //##: TODO - refactor this deep nesting
-                    eprintln!("  Error getting followers from the database: [{:#?}]", e);
-                    continue;
-                }
+            if sweep || actor.last_episode_published == 0 {
+                episode_check(&pi_client, actor.pcid).await;
+                api_call_count += 1;
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            } else if let Some(episodes) = recent_episodes.remove(&actor.pcid) {
+                recent_episode_check(actor.pcid, &episodes).await;
             }
+        }
 
-            thread::sleep(Duration::from_millis(500));
+        last_run_time = start_time;
+        if sweep {
+            last_sweep_time = start_time;
         }
 
         println!("TRACKER RUN: [{}] seconds.", SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs() - start_time);
-        println!("TRACKER: [{}] podcasts being followed.", actor_count);
+        println!("TRACKER: [{}] podcasts being followed, [{}] looked up one by one.", actor_count, api_call_count);
+        if let Some(stats) = pi_client.cache_stats() {
+            println!(
+                "TRACKER: PI cache [{}] hits, [{}] misses, [{}] evictions, [{}] entries.",
+                stats.hits, stats.misses, stats.evictions, stats.entries
+            );
+        }
+    }
+}
+
+//##: A feed we track was podpinged, so look for new episodes once the index has had a chance to pick them up.
+//##: If the first look finds nothing, the index may still be behind, so look once more a bit later
+async fn episode_podping_check(pi_client: Arc<dyn PodcastIndexClient>, pcid: u64) {
+    let mut waited = 0;
+    for delay in PODPING_EPISODE_CHECK_DELAYS_SECONDS {
+        tokio::time::sleep(Duration::from_secs(delay - waited)).await;
+        waited = delay;
+        if waited == PODPING_EPISODE_CHECK_DELAYS_SECONDS[0] {
+            PODPING_EPISODE_CHECKS_PENDING.lock().unwrap().retain(|pending| *pending != pcid);
+        }
+
+        pi_client.invalidate_feed(&pcid.to_string());
+        if episode_check(&pi_client, pcid).await {
+            return;
+        }
+    }
+}
+
+//##: Only one check of a podcast at a time, otherwise the sweep and a podping could both announce the same episode
+struct EpisodeCheckClaim(u64);
+
+impl Drop for EpisodeCheckClaim {
+    fn drop(&mut self) {
+        EPISODE_CHECKS_RUNNING.lock().unwrap().retain(|running| *running != self.0);
     }
 }
 
-fn live_item_tracker(api_key: String, api_secret: String) {
+async fn claim_episode_check(pcid: u64) -> EpisodeCheckClaim {
     loop {
-        println!("PODPING: Connecting to podping socket.");
-        let socket_result = connect(
-            Url::parse(format!("wss://api.livewire.io/ws/podping?agent={}", USER_AGENT_PARAM).as_str())
-                .unwrap(),
+        {
+            let mut running = EPISODE_CHECKS_RUNNING.lock().unwrap();
+            if !running.contains(&pcid) {
+                running.push(pcid);
+                return EpisodeCheckClaim(pcid);
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+//##: Announce whatever episodes a podcast has published since we last looked.  True if there were any
+async fn episode_check(pi_client: &Arc<dyn PodcastIndexClient>, pcid: u64) -> bool {
+    let _claim = claim_episode_check(pcid).await;
+
+    let actor = match dbif::get_actor_from_db(&AP_DATABASE_FILE.to_string(), pcid) {
+        Ok(actor_record) => {
+            actor_record
+        }
+        Err(e) => {
+            eprintln!("  Error getting actor from the database: [{:#?}]", e);
+            return false;
+        }
+    };
+
+    //##: Lookup API of podcast
+    println!("  Podcast API Call - [{}]", actor.pcid);
+    let podcast_data = match pi_client.get_episodes(&actor.pcid.to_string(), handler::AP_TRACKER_EPISODE_FETCH).await {
+        Ok(data) => {
+            data
+        }
+        Err(e) => {
+            eprintln!("  PI API call error: [{:#?}].\n", e);
+            return false;
+        }
+    };
+
+    //##: Remember the feed url, so podpings for it can be matched back to this podcast
+    if let Some(episode) = podcast_data.items.iter().find(|episode| !episode.feedUrl.is_empty()) {
+        if episode.feedUrl != actor.feed_url {
+            let _ = dbif::update_actor_feed_url_in_db(&AP_DATABASE_FILE.to_string(), actor.pcid, episode.feedUrl.clone());
+        }
+    }
+
+    announce_new_episodes(&actor, &podcast_data.items)
+}
+
+//##: Same as above, with episodes that came in the batch of recent ones instead of from looking up the podcast
+async fn recent_episode_check(pcid: u64, episodes: &[PIItem]) -> bool {
+    let _claim = claim_episode_check(pcid).await;
+
+    match dbif::get_actor_from_db(&AP_DATABASE_FILE.to_string(), pcid) {
+        Ok(actor) => {
+            announce_new_episodes(&actor, episodes)
+        }
+        Err(e) => {
+            eprintln!("  Error getting actor from the database: [{:#?}]", e);
+            false
+        }
+    }
+}
+
+fn announce_new_episodes(actor: &ActorRecord, episodes: &[PIItem]) -> bool {
+    let followers = match dbif::get_followers_from_db(&AP_DATABASE_FILE.to_string(), actor.pcid) {
+        Ok(follower_list) => {
+            follower_list
+        }
+        Err(e) => {
+            eprintln!("  Error getting followers from the database: [{:#?}]", e);
+            return false;
+        }
+    };
+
+    let seen_guids = dbif::get_seen_episode_guids_from_db(&AP_DATABASE_FILE.to_string(), actor.pcid).unwrap_or_default();
+    let new_episodes = ap_new_episodes(episodes, actor, &seen_guids);
+    if new_episodes.seen.len() > new_episodes.announce.len() {
+        println!(
+            "  [{}] new episodes for: [{}], announcing [{}].",
+            new_episodes.seen.len(), actor.pcid, new_episodes.announce.len()
         );
+    }
+
+    //##: Oldest first, so they land in follower timelines in the order they were published
+    for episode in &new_episodes.announce {
+        let mut shared_inboxes_called = Vec::new();
+        for follower in &followers {
+            if !shared_inboxes_called.contains(&follower.shared_inbox) {
+                let _ = ap_send_episode_note(
+                    actor.pcid,
+                    episode,
+                    follower.shared_inbox.clone(),
+                    false,
+                    None
+                );
+                shared_inboxes_called.push(follower.shared_inbox.clone());
+            }
+        }
+    }
+
+    //##: Move the high water mark up past everything handled this time
+    for episode in &new_episodes.seen {
+        let _ = dbif::add_seen_episode_to_db(&AP_DATABASE_FILE.to_string(), SeenEpisodeRecord {
+            pcid: actor.pcid,
+            guid: episode.guid.clone(),
+            published: episode.datePublished,
+        });
+    }
+    if let Some(newest_episode) = new_episodes.seen.last() {
+        let _ = dbif::update_actor_last_episode_guid_in_db(
+            &AP_DATABASE_FILE.to_string(),
+            actor.pcid,
+            newest_episode.guid.clone(),
+        );
+        let _ = dbif::update_actor_last_episode_published_in_db(
+            &AP_DATABASE_FILE.to_string(),
+            actor.pcid,
+            new_episodes.last_published,
+        );
+        let _ = dbif::remove_seen_episodes_from_db_before(
+            &AP_DATABASE_FILE.to_string(),
+            actor.pcid,
+            new_episodes.last_published,
+        );
+    }
+
+    !new_episodes.seen.is_empty()
+}
 
-        let (mut socket, _response) = match socket_result {
-            Ok(conn) => {
-                println!("PODPING: Connected to podping socket.");
-                conn
+async fn live_item_tracker(pi_client: Arc<dyn PodcastIndexClient>, podping_config: PodpingSourceConfig) {
+    let mut podping_source = podping_config.open();
+    let mut podping_cursor;
+    if podping_source.resumable() {
+        podping_cursor = PodpingCursor::new(Some(AP_DATABASE_FILE.to_string()));
+    } else {
+        podping_cursor = PodpingCursor::new(None);
+    }
+
+    loop {
+        println!("PODPING: Connecting to podping source: [{:?}]", podping_source);
+        match podping_source.connect().await {
+            Ok(_) => {
+                println!("PODPING: Connected to podping source.");
+                podping_cursor.reconnected();
+            }
+            Err(PodpingError::Finished) => {
+                break;
             }
             Err(e) => {
-                eprintln!("PODPING: Failed to connect to podping socket: [{:#?}]", e);
+                eprintln!("PODPING: Failed to connect to podping source: [{}]", e);
                 // Wait before retrying the connection
-                thread::sleep(Duration::from_secs(5));
+                tokio::time::sleep(Duration::from_secs(5)).await;
                 continue;
             }
-        };
+        }
 
         // Main read loop
         loop {
-            let msg = match socket.read_message() {
-                Ok(msg) => msg,
-                Err(e) => {
-                    eprintln!("PODPING: Socket disconnected or error reading message: [{:#?}]", e);
-                    // Sleep a bit and break to outer loop to reconnect
-                    thread::sleep(Duration::from_secs(5));
-                    break;
-                }
-            };
-
-            match serde_json::from_str(msg.to_text().unwrap()) {
-                Ok(data) => {
-                    let socket_payload: SocketPayload = data;
-                    for podping in socket_payload.p {
+            match podping_source.next_payload().await {
+                Ok(socket_payload) => {
+                    for podping in podping_cursor.accept(socket_payload) {
+                        if podping.p.reason == "update" {
+                            for iri in &podping.p.iris {
+                                podping_episode_update(pi_client.clone(), iri);
+                            }
+                        }
                         if podping.p.reason == "live" {
                             println!("*****LIVE PODPING: [{:#?}]", podping);
-                            let first_iri = podping.p.iris.get(0);
+                            let first_iri = podping.p.iris.first();
                             if first_iri.is_none() {
                                 continue;
                             }
-                            //##: Sleep to let the index catch up
-                            thread::sleep(Duration::from_millis(LOOP_TIMER_MILLISECONDS));
-                            match api_block_get_live_items(
-                                &api_key,
-                                &api_secret,
-                                first_iri.unwrap()
-                            ) {
-                                Ok(api_response) => {
-                                    match serde_json::from_str(api_response.as_str()) {
-                                        Ok(response_data) => {
-                                            let live_item_data: PILiveItems = response_data;
-                                            for live_item in live_item_data.liveItems {
-                                                if live_item.status == "live" {
-                                                    println!("*****PODPING LIVE - {} {}",
-                                                             live_item.feedId,
-                                                             live_item.status
-                                                    );
-                                                    match dbif::get_followers_from_db(&AP_DATABASE_FILE.to_string(), live_item.feedId) {
-                                                        Ok(followers) => {
-                                                            let mut shared_inboxes_called = Vec::new();
-                                                            for follower in followers {
-                                                                if !shared_inboxes_called.contains(&follower.shared_inbox) {
-                                                                    let _ = ap_block_send_live_note(
-                                                                        live_item.feedId,
-                                                                        &live_item,
-                                                                        follower.shared_inbox.clone(),
-                                                                    );
-                                                                    shared_inboxes_called.push(follower.shared_inbox.clone());
-                                                                }
-                                                            }
-                                                        }
-                                                        Err(_e) => {
-                                                            //##: TODO - refactor this deep nesting
-                                                        }
-                                                    }
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                        Err(_e) => {
-                                            //##: TODO - refactor this deep nesting
-                                        }
-                                    }
-                                }
-                                Err(_e) => {
-                                    //##: TODO - refactor this deep nesting
-                                }
-                            }
-
+                            //##: Announce from its own task so the socket keeps being read while we wait
+                            tokio::spawn(live_item_announce(
+                                pi_client.clone(),
+                                first_iri.unwrap().clone(),
+                            ));
                         }
                     }
+                    podping_cursor.handled();
+                }
+                Err(PodpingError::Unreadable(e)) => {
+                    eprintln!("PODPING PARSE ERR: [{}]", e);
+                }
+                Err(PodpingError::Finished) => {
+                    break;
                 }
                 Err(e) => {
-                    eprintln!("PODPING PARSE ERR: [{:#?}]", e);
+                    eprintln!("PODPING: Source disconnected or error reading message: [{}]", e);
+                    // Sleep a bit and break to outer loop to reconnect
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    break;
                 }
             }
         }
         // When we reach here, the inner loop has broken, so we'll reconnect
-        println!("PODPING: Attempting to reconnect to socket...");
+        println!("PODPING: Attempting to reconnect to podping source...");
     }
-}
\ No newline at end of file
+
+    //##: A replay that has run out.  Stay here rather than return, so the task isn't restarted to play it again
+    println!("PODPING: Podping source finished.");
+    std::future::pending::<()>().await;
+}
+
+//##: Check a pinged feed for new episodes, if it's one of ours and somebody follows it
+fn podping_episode_update(pi_client: Arc<dyn PodcastIndexClient>, feed_url: &str) {
+    let actor = match dbif::get_actor_from_db_by_feed_url(&AP_DATABASE_FILE.to_string(), feed_url.to_string()) {
+        Ok(actor_record) => {
+            actor_record
+        }
+        Err(_) => {
+            return;
+        }
+    };
+    if dbif::get_follower_count_from_db(&AP_DATABASE_FILE.to_string(), actor.pcid).unwrap_or(0) == 0 {
+        return;
+    }
+
+    //##: Feeds often ping several times in a row, one check covers them all
+    {
+        let mut pending = PODPING_EPISODE_CHECKS_PENDING.lock().unwrap();
+        if pending.contains(&actor.pcid) {
+            return;
+        }
+        pending.push(actor.pcid);
+    }
+
+    println!("PODPING: Episode update for: [{}|{}]", actor.pcid, feed_url);
+    tokio::spawn(episode_podping_check(pi_client, actor.pcid));
+}
+
+async fn live_item_announce(pi_client: Arc<dyn PodcastIndexClient>, feed_url: String) {
+    //##: Sleep to let the index catch up
+    tokio::time::sleep(Duration::from_millis(LOOP_TIMER_MILLISECONDS)).await;
+
+    let live_item_data: PILiveItems = match pi_client.get_live_items(&feed_url).await {
+        Ok(response_data) => {
+            response_data
+        }
+        Err(e) => {
+            eprintln!("PODPING: Live items api call error: [{:#?}]", e);
+            return;
+        }
+    };
+
+    for live_item in live_item_data.liveItems {
+        if live_item.status == "live" {
+            println!("*****PODPING LIVE - {} {}",
+                     live_item.feedId,
+                     live_item.status
+            );
+            match dbif::get_followers_from_db(&AP_DATABASE_FILE.to_string(), live_item.feedId) {
+                Ok(followers) => {
+                    let mut shared_inboxes_called = Vec::new();
+                    for follower in followers {
+                        if !shared_inboxes_called.contains(&follower.shared_inbox) {
+                            let _ = ap_send_live_note(
+                                live_item.feedId,
+                                &live_item,
+                                follower.shared_inbox.clone(),
+                            );
+                            shared_inboxes_called.push(follower.shared_inbox.clone());
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("PODPING: Error getting followers from the database: [{:#?}]", e);
+                }
+            }
+            break;
+        }
+    }
+}
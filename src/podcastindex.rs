@@ -0,0 +1,889 @@
+//##: Podcast Index API access.  Everything the bridge asks the index goes through the PodcastIndexClient trait,
+//##: so the real api can be swapped for a directory of canned responses when running offline
+use crate::USER_AGENT_PARAM;
+use async_trait::async_trait;
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use sha1::{Digest, Sha1};
+use std::error::Error;
+use std::fmt;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use dbif::ApiCacheRecord;
+
+
+//Globals ----------------------------------------------------------------------------------------------------
+pub const PI_DEFAULT_API_URL: &str = "https://api.podcastindex.org/api/1.0";
+pub const PI_DEFAULT_HUB_URL: &str = "https://hub.podcastindex.org";
+pub const PI_DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+pub const PI_DEFAULT_CACHE_MAX_ENTRIES: usize = 10000;
+const PI_CACHE_NOT_FOUND_TTL_SECONDS: u64 = 300;
+
+//Structs ----------------------------------------------------------------------------------------------------
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIFeed {
+    pub id: u64,
+    pub podcastGuid: String,
+    pub medium: String,
+    pub title: String,
+    pub url: String,
+    pub originalUrl: String,
+    pub link: String,
+    pub description: String,
+    pub author: String,
+    pub ownerName: String,
+    pub image: String,
+    pub artwork: String,
+    pub episodeCount: u64,
+    pub funding: Option<PIFunding>,
+    #[serde(default)]
+    pub podroll: Option<Vec<PIRemoteItem>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIRemoteItem {
+    pub feedGuid: String,
+    #[serde(default)]
+    pub feedUrl: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIFunding {
+    pub url: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PISearchFeed {
+    pub id: u64,
+    pub title: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PISearchResult {
+    #[serde(default)]
+    pub feeds: Vec<PISearchFeed>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIPodcast {
+    pub status: String,
+    pub feed: PIFeed,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIItem {
+    pub id: u64,
+    pub title: String,
+    pub link: String,
+    pub description: String,
+    pub guid: String,
+    pub datePublished: u64,
+    pub datePublishedPretty: String,
+    pub enclosureUrl: String,
+    pub enclosureType: String,
+    #[serde(default)]
+    pub duration: u64,
+    pub image: String,
+    pub feedImage: String,
+    #[serde(default)]
+    pub feedUrl: String,
+    #[serde(default)]
+    pub podcastGuid: String,
+    pub feedId: u64,
+    pub feedItunesId: Option<u64>,
+    pub socialInteract: Option<Vec<PISocialInteract>>,
+    pub transcripts: Option<Vec<PITranscript>>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PITranscript {
+    pub url: Option<String>,
+    pub r#type: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[allow(non_camel_case_types)]
+#[derive(Serialize, Deserialize)]
+pub enum PISocialInteractProtocol {
+    activitypub,
+    xmpp,
+    bluesky,
+    nostr,
+    twitter,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PISocialInteract {
+    pub uri: Option<String>,
+    pub protocol: Option<PISocialInteractProtocol>,
+    pub accountId: Option<String>,
+    pub accountUrl: Option<String>,
+    pub priority: Option<u64>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIEpisodes {
+    pub status: String,
+    pub items: Vec<PIItem>,
+    pub count: u64,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize)]
+pub struct PIEpisode {
+    pub status: String,
+    pub episode: PIItem,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PILiveItems {
+    pub status: String,
+    pub liveItems: Vec<PILiveItem>,
+    pub count: u64,
+    pub query: String,
+    pub description: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PILiveItem {
+    pub id: u64,
+    pub title: String,
+    pub link: String,
+    pub description: String,
+    pub guid: String,
+    pub datePublished: u64,
+    pub datePublishedPretty: String,
+    pub enclosureUrl: String,
+    pub enclosureType: String,
+    pub startTime: Option<u64>,
+    pub endTime: Option<u64>,
+    pub status: String,
+    pub contentLink: String,
+    pub image: String,
+    pub feedImage: String,
+    pub feedId: u64,
+    pub feedUrl: String,
+    pub podcastGuid: String,
+    pub feedItunesId: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum PIError {
+    NotFound(String),
+    Failed(String),
+}
+
+impl fmt::Display for PIError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PIError::NotFound(what) => write!(f, "Not found in the index: {}", what),
+            PIError::Failed(why) => write!(f, "Index request failed: {}", why),
+        }
+    }
+}
+
+impl Error for PIError {}
+
+pub type PIResult<T> = Result<T, PIError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PICacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: u64,
+}
+
+
+//Client trait -----------------------------------------------------------------------------------------------
+//##: Implementors only have to fetch raw response bodies.  The typed lookups are shared so that every
+//##: client parses and reports missing podcasts the same way
+#[async_trait]
+pub trait PodcastIndexClient: Send + Sync + fmt::Debug {
+    async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String>;
+
+    async fn hub_rescan(&self, feed_id: &str) -> PIResult<String>;
+
+    fn cache_stats(&self) -> Option<PICacheStats> {
+        None
+    }
+
+    //##: Drop anything held about a feed, for when we've been told it just changed
+    fn invalidate_feed(&self, _feed_id: &str) {}
+
+    async fn get_podcast(&self, feed_id: &str) -> PIResult<PIPodcast> {
+        let body = self.fetch("/podcasts/byfeedid", &[("id", feed_id)]).await?;
+        return parse_response(&body);
+    }
+
+    async fn get_podcast_by_guid(&self, guid: &str) -> PIResult<PIPodcast> {
+        let body = self.fetch("/podcasts/byguid", &[("guid", guid)]).await?;
+        return parse_response(&body);
+    }
+
+    async fn get_podcast_by_feedurl(&self, feed_url: &str) -> PIResult<PIPodcast> {
+        let body = self.fetch("/podcasts/byfeedurl", &[("url", feed_url)]).await?;
+        return parse_response(&body);
+    }
+
+    async fn search_podcasts_by_title(&self, title: &str) -> PIResult<PISearchResult> {
+        let body = self.fetch("/search/bytitle", &[("q", title), ("similar", "")]).await?;
+        return parse_response(&body);
+    }
+
+    async fn get_episodes(&self, feed_id: &str, max: u64) -> PIResult<PIEpisodes> {
+        let max = max.to_string();
+        let body = self.fetch("/episodes/byfeedid", &[("id", feed_id), ("max", &max)]).await?;
+        return parse_response(&body);
+    }
+
+    //##: Only episodes published at or after `since`, newest first
+    async fn get_episodes_since(&self, feed_id: &str, since: u64, max: u64) -> PIResult<PIEpisodes> {
+        let since = since.to_string();
+        let max = max.to_string();
+        let body = self.fetch("/episodes/byfeedid", &[("id", feed_id), ("since", &since), ("max", &max)]).await?;
+        return parse_response(&body);
+    }
+
+    async fn get_recent_episodes(&self, max: u64) -> PIResult<PIEpisodes> {
+        let max = max.to_string();
+        let body = self.fetch("/recent/episodes", &[("max", &max)]).await?;
+        return parse_response(&body);
+    }
+
+    async fn get_episode(&self, feed_id: &str, guid: &str) -> PIResult<PIEpisode> {
+        let body = self.fetch("/episodes/byguid", &[("guid", guid), ("feedid", feed_id)]).await?;
+        return parse_response(&body);
+    }
+
+    async fn get_live_items(&self, feed_url: &str) -> PIResult<PILiveItems> {
+        let body = self.fetch("/live/byfeedurl", &[("url", feed_url)]).await?;
+        return parse_response(&body);
+    }
+}
+
+
+//Podcast Index API ------------------------------------------------------------------------------------------
+#[derive(Debug)]
+pub struct PodcastIndexApi {
+    key: String,
+    secret: String,
+    api_url: String,
+    hub_url: String,
+    client: reqwest::Client,
+}
+
+impl PodcastIndexApi {
+    pub fn new(key: &str, secret: &str, api_url: &str, timeout: Duration) -> Result<PodcastIndexApi, Box<dyn Error>> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()?;
+
+        Ok(PodcastIndexApi {
+            key: key.to_string(),
+            secret: secret.to_string(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            hub_url: PI_DEFAULT_HUB_URL.to_string(),
+            client,
+        })
+    }
+
+    //##: The index wants an sha1 hash of the key, secret and current time (as a string) concatenated together,
+    //##: as a lowercase hex string
+    fn auth_headers(&self) -> PIResult<header::HeaderMap> {
+        let api_time = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs().to_string();
+        let mut hasher = Sha1::new();
+        hasher.update(format!("{}{}{}", self.key, self.secret, api_time));
+        let api_hash: String = format!("{:X}", hasher.finalize()).to_lowercase();
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert("User-Agent", header::HeaderValue::from_static(USER_AGENT_PARAM));
+        for (name, value) in [("X-Auth-Date", api_time), ("X-Auth-Key", self.key.clone()), ("Authorization", api_hash)] {
+            match header::HeaderValue::from_str(value.as_str()) {
+                Ok(header_value) => {
+                    headers.insert(name, header_value);
+                }
+                Err(e) => {
+                    return Err(PIError::Failed(format!("Bad {} header: [{}]", name, e)));
+                }
+            }
+        }
+
+        Ok(headers)
+    }
+
+    async fn get(&self, url: String) -> PIResult<String> {
+        let headers = self.auth_headers()?;
+
+        let response = match self.client.get(url.as_str()).headers(headers).send().await {
+            Ok(res) => {
+                println!("  Response: [{}]", res.status());
+                res
+            }
+            Err(e) => {
+                eprintln!("  Error: [{}]", e);
+                return Err(PIError::Failed(format!("Error making pi api request: [{}]", e)));
+            }
+        };
+
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(PIError::NotFound(url));
+        }
+        if !status.is_success() {
+            return Err(PIError::Failed(format!("Index returned [{}] for: [{}]", status, url)));
+        }
+        match response.text().await {
+            Ok(body) => {
+                Ok(body)
+            }
+            Err(e) => {
+                Err(PIError::Failed(format!("Error reading pi api response: [{}]", e)))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PodcastIndexClient for PodcastIndexApi {
+    async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String> {
+        println!("  PI API Request: {}", endpoint);
+        return self.get(format!("{}{}?{}", self.api_url, endpoint, encode_params(params))).await;
+    }
+
+    async fn hub_rescan(&self, feed_id: &str) -> PIResult<String> {
+        println!("PI HUB Request: /pubnotify?id={}", feed_id);
+        return self.get(format!("{}/pubnotify?{}", self.hub_url, encode_params(&[("id", feed_id)]))).await;
+    }
+}
+
+
+//Fixtures ---------------------------------------------------------------------------------------------------
+//##: Serves canned api responses from a directory, laid out like the api itself.  The file for a request is
+//##: named after its lookup values (everything but `max` and `since`), so /podcasts/byfeedid?id=920666 is read from
+//##: <dir>/podcasts/byfeedid/920666.json and /recent/episodes?max=10 from <dir>/recent/episodes.json
+#[derive(Debug)]
+pub struct PodcastIndexFixtures {
+    dir: PathBuf,
+}
+
+impl PodcastIndexFixtures {
+    pub fn new(dir: &str) -> PodcastIndexFixtures {
+        PodcastIndexFixtures {
+            dir: PathBuf::from(dir),
+        }
+    }
+
+    fn fixture_path(&self, endpoint: &str, params: &[(&str, &str)]) -> PathBuf {
+        let endpoint = endpoint.trim_matches('/');
+        let key = fixture_key(params);
+        if key.is_empty() {
+            return self.dir.join(format!("{}.json", endpoint));
+        }
+
+        self.dir.join(endpoint).join(format!("{}.json", key))
+    }
+}
+
+#[async_trait]
+impl PodcastIndexClient for PodcastIndexFixtures {
+    async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String> {
+        let fixture_path = self.fixture_path(endpoint, params);
+        println!("  PI Fixture Request: [{}]", fixture_path.display());
+
+        match std::fs::read_to_string(&fixture_path) {
+            Ok(body) => {
+                return Ok(body);
+            }
+            Err(_) => {
+                return Err(PIError::NotFound(fixture_path.display().to_string()));
+            }
+        }
+    }
+
+    async fn hub_rescan(&self, feed_id: &str) -> PIResult<String> {
+        println!("PI Fixture HUB Request: /pubnotify?id={}", feed_id);
+        return Ok("Fixture rescan requested.".to_string());
+    }
+}
+
+
+//Cache ------------------------------------------------------------------------------------------------------
+//##: Sits in front of another client and keeps raw response bodies for a while, so that the same podcast or
+//##: episode being dereferenced by thousands of remote instances only reaches the index once.  Lookups the
+//##: index has no answer for are kept too, for a shorter time
+#[derive(Debug)]
+pub struct PodcastIndexCache {
+    inner: Arc<dyn PodcastIndexClient>,
+    entries: Mutex<HashMap<String, PICacheEntry>>,
+    in_flight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    max_entries: usize,
+    database: Option<String>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    stores: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct PICacheEntry {
+    body: Option<String>,
+    expires: u64,
+}
+
+impl PodcastIndexCache {
+    pub fn new(inner: Arc<dyn PodcastIndexClient>, max_entries: usize, database: Option<String>) -> PodcastIndexCache {
+        let cache = PodcastIndexCache {
+            inner,
+            entries: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashMap::new()),
+            max_entries,
+            database,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            stores: AtomicU64::new(0),
+        };
+        cache.trim_database(now_seconds());
+
+        cache
+    }
+
+    fn lookup(&self, key: &str, now: u64) -> Option<PICacheEntry> {
+        if let Some(entry) = self.entries.lock().unwrap().get(key) {
+            if entry.expires > now {
+                return Some(entry.clone());
+            }
+        }
+
+        //##: Fall back to what an earlier run left in the database
+        let database_file = self.database.as_ref()?;
+        let record = dbif::get_api_cache_entry_from_db(database_file, key.to_string(), now).ok()?;
+        let mut entry = PICacheEntry {
+            body: Some(record.body),
+            expires: record.expires,
+        };
+        if record.not_found == 1 {
+            entry.body = None;
+        }
+        self.remember(key, entry.clone(), now);
+
+        Some(entry)
+    }
+
+    fn store(&self, key: &str, entry: PICacheEntry, now: u64) {
+        self.remember(key, entry.clone(), now);
+
+        if let Some(database_file) = &self.database {
+            let mut not_found = 0;
+            if entry.body.is_none() {
+                not_found = 1;
+            }
+            let _ = dbif::add_api_cache_entry_to_db(database_file, ApiCacheRecord {
+                key: key.to_string(),
+                body: entry.body.unwrap_or_default(),
+                not_found,
+                expires: entry.expires,
+            });
+
+            //##: Outside requests decide a lot of the keys, so the table is trimmed back to the size of the map
+            //##: every tenth of that many stores.  It never gets more than 10% bigger than the map
+            let trim_every = (self.max_entries as u64 / 10).max(1);
+            if (self.stores.fetch_add(1, Ordering::Relaxed) + 1).is_multiple_of(trim_every) {
+                self.trim_database(now);
+            }
+        }
+    }
+
+    fn trim_database(&self, now: u64) {
+        if let Some(database_file) = &self.database {
+            let _ = dbif::remove_expired_api_cache_entries_from_db(database_file, now);
+            let _ = dbif::remove_api_cache_entries_from_db_beyond(database_file, self.max_entries as u64);
+        }
+    }
+
+    fn remember(&self, key: &str, entry: PICacheEntry, now: u64) {
+        let mut entries = self.entries.lock().unwrap();
+
+        //##: Make room by dropping what has expired, then whatever is closest to expiring
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            entries.retain(|_, cached| cached.expires > now);
+            while entries.len() >= self.max_entries {
+                let oldest_key = match entries.iter().min_by_key(|(_, cached)| cached.expires) {
+                    Some((cached_key, _)) => {
+                        cached_key.clone()
+                    }
+                    None => {
+                        break;
+                    }
+                };
+                entries.remove(&oldest_key);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        if self.max_entries > 0 {
+            entries.insert(key.to_string(), entry);
+        }
+    }
+
+    fn cached(&self, key: &str, now: u64) -> Option<PIResult<String>> {
+        let entry = self.lookup(key, now)?;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        match entry.body {
+            Some(body) => {
+                Some(Ok(body))
+            }
+            None => {
+                Some(Err(PIError::NotFound(key.to_string())))
+            }
+        }
+    }
+
+    async fn fetch_and_store(&self, endpoint: &str, params: &[(&str, &str)], key: &str, ttl: u64) -> PIResult<String> {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let now = now_seconds();
+        match self.inner.fetch(endpoint, params).await {
+            Ok(body) => {
+                let mut expires = now + ttl;
+                if not_found_description(&body).is_some() {
+                    expires = now + ttl.min(PI_CACHE_NOT_FOUND_TTL_SECONDS);
+                }
+                self.store(key, PICacheEntry { body: Some(body.clone()), expires }, now);
+                Ok(body)
+            }
+            Err(PIError::NotFound(what)) => {
+                let expires = now + ttl.min(PI_CACHE_NOT_FOUND_TTL_SECONDS);
+                self.store(key, PICacheEntry { body: None, expires }, now);
+                Err(PIError::NotFound(what))
+            }
+            Err(e) => {
+                Err(e)
+            }
+        }
+    }
+
+    fn forget(&self, prefix: &str) {
+        self.entries.lock().unwrap().retain(|key, _| !key.starts_with(prefix));
+        if let Some(database_file) = &self.database {
+            let _ = dbif::remove_api_cache_entries_from_db_by_prefix(database_file, prefix.to_string());
+        }
+    }
+}
+
+#[async_trait]
+impl PodcastIndexClient for PodcastIndexCache {
+    async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String> {
+        let ttl = cache_ttl(endpoint);
+        if ttl == 0 {
+            return self.inner.fetch(endpoint, params).await;
+        }
+
+        let key = format!("{}?{}", endpoint, encode_params(params));
+        if let Some(result) = self.cached(&key, now_seconds()) {
+            return result;
+        }
+
+        //##: Only one request per key goes out to the index at a time.  Everyone else asking for the same thing
+        //##: waits for it, then finds the answer in the cache
+        let key_lock = self.in_flight.lock().unwrap().entry(key.clone()).or_default().clone();
+        let fetching = key_lock.lock().await;
+        let result = match self.cached(&key, now_seconds()) {
+            Some(result) => result,
+            None => self.fetch_and_store(endpoint, params, &key, ttl).await,
+        };
+        drop(fetching);
+
+        //##: The last one out clears the lock away
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if Arc::strong_count(&key_lock) <= 2 {
+            in_flight.remove(&key);
+        }
+
+        return result;
+    }
+
+    //##: Somebody asked for the feed to be looked at again, so don't keep answering with what we had
+    async fn hub_rescan(&self, feed_id: &str) -> PIResult<String> {
+        self.invalidate_feed(feed_id);
+        return self.inner.hub_rescan(feed_id).await;
+    }
+
+    fn cache_stats(&self) -> Option<PICacheStats> {
+        Some(PICacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len() as u64,
+        })
+    }
+
+    fn invalidate_feed(&self, feed_id: &str) {
+        self.forget(&format!("/podcasts/byfeedid?{}", encode_params(&[("id", feed_id)])));
+        self.forget(&format!("/episodes/byfeedid?{}&", encode_params(&[("id", feed_id)])));
+    }
+}
+
+
+//Utilities --------------------------------------------------------------------------------------------------
+//##: The index answers lookups for things it doesn't know with a 200 and an empty result, like "feed": [], so
+//##: a body that won't parse is only an error when it isn't one of those
+fn parse_response<T: DeserializeOwned>(body: &str) -> PIResult<T> {
+    match serde_json::from_str::<T>(body) {
+        Ok(data) => {
+            Ok(data)
+        }
+        Err(e) => {
+            if let Some(description) = not_found_description(body) {
+                return Err(PIError::NotFound(description));
+            }
+            Err(PIError::Failed(format!("Unexpected pi api response: [{}]", e)))
+        }
+    }
+}
+
+fn not_found_description(body: &str) -> Option<String> {
+    let value = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    let status_false = value.get("status").map(|status| status == "false" || status == false).unwrap_or(false);
+    let empty_result = ["feed", "episode"].iter().any(|field| {
+        match value.get(field) {
+            Some(serde_json::Value::Array(items)) => items.is_empty(),
+            Some(serde_json::Value::Null) => true,
+            _ => false,
+        }
+    });
+    if !status_false && !empty_result {
+        return None;
+    }
+
+    Some(value.get("description").and_then(|d| d.as_str()).unwrap_or("No results.").to_string())
+}
+
+//##: How long a response from each endpoint stays good.  Live items are never cached, since a podping means
+//##: something just changed
+fn cache_ttl(endpoint: &str) -> u64 {
+    match endpoint {
+        "/podcasts/byfeedid" | "/podcasts/byguid" | "/podcasts/byfeedurl" => 1800,
+        "/episodes/byguid" => 3600,
+        "/search/bytitle" => 3600,
+        "/episodes/byfeedid" | "/recent/episodes" => 60,
+        _ => 0,
+    }
+}
+
+fn now_seconds() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("Time mismatch.").as_secs()
+}
+
+fn encode_params(params: &[(&str, &str)]) -> String {
+    params.iter()
+        .map(|(name, value)| {
+            if value.is_empty() {
+                return name.to_string();
+            }
+            format!("{}={}", name, urlencoding::encode(value))
+        })
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+fn fixture_key(params: &[(&str, &str)]) -> String {
+    params.iter()
+        .filter(|(name, value)| *name != "max" && *name != "since" && !value.is_empty())
+        .map(|(_, value)| {
+            value.chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> PodcastIndexFixtures {
+        PodcastIndexFixtures::new(concat!(env!("CARGO_MANIFEST_DIR"), "/sample_data/podcastindex"))
+    }
+
+    #[test]
+    fn test_encode_params() {
+        assert_eq!(encode_params(&[("id", "920666"), ("max", "1")]), "id=920666&max=1");
+        assert_eq!(encode_params(&[("url", "https://a.example/feed?x=1")]), "url=https%3A%2F%2Fa.example%2Ffeed%3Fx%3D1");
+        assert_eq!(encode_params(&[("q", "no agenda"), ("similar", "")]), "q=no%20agenda&similar");
+    }
+
+    #[test]
+    fn test_fixture_key() {
+        assert_eq!(fixture_key(&[("id", "920666"), ("max", "20")]), "920666");
+        assert_eq!(fixture_key(&[("max", "20")]), "");
+        assert_eq!(fixture_key(&[("id", "920666"), ("since", "1700000000"), ("max", "20")]), "920666");
+        assert_eq!(fixture_key(&[("url", "https://a.example/feed.xml")]), "https___a.example_feed.xml");
+        assert_eq!(fixture_key(&[("guid", "abc 1"), ("feedid", "5")]), "abc_1_5");
+    }
+
+    #[test]
+    fn test_parse_response_not_found() {
+        let body = r#"{"status":"true","feed":[],"description":"No feeds match this id."}"#;
+        match parse_response::<PIPodcast>(body) {
+            Err(PIError::NotFound(description)) => assert_eq!(description, "No feeds match this id."),
+            _ => panic!("expected not found"),
+        }
+        match parse_response::<PIPodcast>("<html>Bad gateway</html>") {
+            Err(PIError::Failed(_)) => {}
+            _ => panic!("expected failure"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixtures_podcast_and_episodes() {
+        let pi_client = fixtures();
+        let podcast = pi_client.get_podcast("920666").await.unwrap();
+        assert_eq!(podcast.feed.id, 920666);
+        assert_eq!(podcast.feed.title, "Podcasting 2.0");
+
+        let episodes = pi_client.get_episodes("920666", 1).await.unwrap();
+        assert_eq!(episodes.items.len(), 2);
+        assert_eq!(episodes.items[0].feedId, 920666);
+
+        match pi_client.get_podcast("1").await {
+            Err(PIError::NotFound(_)) => {}
+            _ => panic!("expected not found"),
+        }
+    }
+
+    #[derive(Debug)]
+    struct CountingClient {
+        inner: PodcastIndexFixtures,
+        fetches: AtomicU64,
+    }
+
+    #[async_trait]
+    impl PodcastIndexClient for CountingClient {
+        async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String> {
+            self.fetches.fetch_add(1, Ordering::Relaxed);
+            return self.inner.fetch(endpoint, params).await;
+        }
+
+        async fn hub_rescan(&self, feed_id: &str) -> PIResult<String> {
+            return self.inner.hub_rescan(feed_id).await;
+        }
+    }
+
+    fn counting_client() -> Arc<CountingClient> {
+        Arc::new(CountingClient {
+            inner: fixtures(),
+            fetches: AtomicU64::new(0),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_and_negative_caching() {
+        let counting = counting_client();
+        let cache = PodcastIndexCache::new(counting.clone(), 10, None);
+
+        assert_eq!(cache.get_podcast("920666").await.unwrap().feed.id, 920666);
+        assert_eq!(cache.get_podcast("920666").await.unwrap().feed.id, 920666);
+        assert!(matches!(cache.get_podcast("1").await, Err(PIError::NotFound(_))));
+        assert!(matches!(cache.get_podcast("1").await, Err(PIError::NotFound(_))));
+        assert_eq!(counting.fetches.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.cache_stats(), Some(PICacheStats { hits: 2, misses: 2, evictions: 0, entries: 2 }));
+
+        //##: Live items always go through
+        let _ = cache.get_live_items("https://example.com/feed.xml").await;
+        let _ = cache.get_live_items("https://example.com/feed.xml").await;
+        assert_eq!(counting.fetches.load(Ordering::Relaxed), 4);
+
+        //##: A rescan drops what we had for the feed
+        let _ = cache.hub_rescan("920666").await;
+        let _ = cache.get_podcast("920666").await;
+        assert_eq!(counting.fetches.load(Ordering::Relaxed), 5);
+    }
+
+    #[derive(Debug)]
+    struct SlowClient {
+        counting: Arc<CountingClient>,
+    }
+
+    #[async_trait]
+    impl PodcastIndexClient for SlowClient {
+        async fn fetch(&self, endpoint: &str, params: &[(&str, &str)]) -> PIResult<String> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            return self.counting.fetch(endpoint, params).await;
+        }
+
+        async fn hub_rescan(&self, feed_id: &str) -> PIResult<String> {
+            return self.counting.hub_rescan(feed_id).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_concurrent_misses() {
+        let counting = counting_client();
+        let cache = Arc::new(PodcastIndexCache::new(Arc::new(SlowClient { counting: counting.clone() }), 10, None));
+
+        //##: A burst of lookups for the same podcast only reaches the index once
+        let mut lookups = tokio::task::JoinSet::new();
+        for _ in 0..20 {
+            let cache = cache.clone();
+            lookups.spawn(async move {
+                return cache.get_podcast("920666").await.map(|podcast| podcast.feed.id);
+            });
+        }
+        while let Some(lookup) = lookups.join_next().await {
+            assert_eq!(lookup.unwrap().unwrap(), 920666);
+        }
+        assert_eq!(counting.fetches.load(Ordering::Relaxed), 1);
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cache_size_bound() {
+        let counting = counting_client();
+        let cache = PodcastIndexCache::new(counting.clone(), 2, None);
+
+        for feed_id in ["1", "2", "3", "4"] {
+            let _ = cache.get_podcast(feed_id).await;
+        }
+        let stats = cache.cache_stats().unwrap();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.evictions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_database_size_bound() {
+        let database_file = concat!(env!("CARGO_MANIFEST_DIR"), "/target/podcastindex-cache-test.db").to_string();
+        let _ = std::fs::create_dir_all(std::path::Path::new(&database_file).parent().unwrap());
+        let _ = std::fs::remove_file(&database_file);
+        dbif::create_database(&database_file).unwrap();
+
+        let cache = PodcastIndexCache::new(counting_client(), 2, Some(database_file.clone()));
+        for feed_id in ["1", "2", "3", "4"] {
+            let _ = cache.get_podcast(feed_id).await;
+        }
+
+        //##: Only the newest entries are left in the table
+        let now = now_seconds();
+        for (feed_id, kept) in [("1", false), ("2", false), ("3", true), ("4", true)] {
+            let key = format!("/podcasts/byfeedid?id={}", feed_id);
+            assert_eq!(dbif::get_api_cache_entry_from_db(&database_file, key, now).is_ok(), kept);
+        }
+    }
+}
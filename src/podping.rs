@@ -0,0 +1,465 @@
+//##: Podping input.  Everything the live tracker reads comes through the PodpingSource trait, so the LiveWire
+//##: websocket can be swapped for another socket, or for a file of recorded messages when running offline
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader, Lines};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+
+//Globals ----------------------------------------------------------------------------------------------------
+pub const PODPING_DEFAULT_SOCKET_URL: &str = "wss://api.livewire.io/ws/podping";
+const PODPING_BLOCK_STATE: &str = "podping_block";
+const PODPING_BLOCK_SECONDS: u64 = 3;
+const PODPING_SEEN_IDS: usize = 1000;
+
+//Structs ----------------------------------------------------------------------------------------------------
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SocketPayload {
+    pub a: String,
+    pub n: u64,
+    pub o: u64,
+    pub p: Vec<PodpingPayload>,
+    pub t: String,
+    pub v: u64,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PodpingPayload {
+    pub a: String,
+    pub i: String,
+    pub p: Podping,
+    pub t: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Podping {
+    pub iris: Vec<String>,
+    pub medium: Option<String>,
+    pub reason: String,
+    pub sessionId: Option<String>,
+    pub timestampNs: Option<u64>,
+    pub version: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum PodpingError {
+    //##: Lost the connection, connect again
+    Disconnected(String),
+    //##: One message that couldn't be read, skip it
+    Unreadable(String),
+    //##: Nothing more is coming, like the end of a replay file
+    Finished,
+}
+
+impl fmt::Display for PodpingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PodpingError::Disconnected(reason) => write!(f, "Disconnected: {}", reason),
+            PodpingError::Unreadable(reason) => write!(f, "Unreadable message: {}", reason),
+            PodpingError::Finished => write!(f, "Finished"),
+        }
+    }
+}
+
+impl std::error::Error for PodpingError {}
+
+pub type PodpingResult<T> = Result<T, PodpingError>;
+
+//##: Where podpings are read from, picked at startup
+#[derive(Debug, Clone)]
+pub enum PodpingSourceConfig {
+    Websocket(String),
+    Replay(Option<PathBuf>),
+}
+
+impl PodpingSourceConfig {
+    pub fn open(&self) -> Box<dyn PodpingSource> {
+        match self {
+            PodpingSourceConfig::Websocket(url) => Box::new(PodpingWebsocket::new(url)),
+            PodpingSourceConfig::Replay(path) => Box::new(PodpingReplay::new(path.clone())),
+        }
+    }
+}
+
+
+//Source trait -----------------------------------------------------------------------------------------------
+#[async_trait]
+pub trait PodpingSource: Send + fmt::Debug {
+    async fn connect(&mut self) -> PodpingResult<()>;
+
+    async fn next_payload(&mut self) -> PodpingResult<SocketPayload>;
+
+    //##: Whether block numbers carry over between runs, so the read position is worth keeping
+    fn resumable(&self) -> bool {
+        true
+    }
+}
+
+
+//Websocket --------------------------------------------------------------------------------------------------
+//##: A stream of podpings over a websocket, like LiveWire's
+pub struct PodpingWebsocket {
+    url: String,
+    socket: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+}
+
+impl PodpingWebsocket {
+    pub fn new(url: &str) -> PodpingWebsocket {
+        PodpingWebsocket {
+            url: url.to_string(),
+            socket: None,
+        }
+    }
+}
+
+impl fmt::Debug for PodpingWebsocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PodpingWebsocket")
+            .field("url", &self.url)
+            .field("connected", &self.socket.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl PodpingSource for PodpingWebsocket {
+    async fn connect(&mut self) -> PodpingResult<()> {
+        self.socket = None;
+        match connect_async(self.url.as_str()).await {
+            Ok((socket, _response)) => {
+                self.socket = Some(socket);
+                return Ok(());
+            }
+            Err(e) => {
+                return Err(PodpingError::Disconnected(format!("{:#?}", e)));
+            }
+        }
+    }
+
+    async fn next_payload(&mut self) -> PodpingResult<SocketPayload> {
+        let socket = match self.socket.as_mut() {
+            Some(connected_socket) => {
+                connected_socket
+            }
+            None => {
+                return Err(PodpingError::Disconnected("Not connected.".to_string()));
+            }
+        };
+
+        loop {
+            match socket.next().await {
+                Some(Ok(msg)) => {
+                    //##: Pings, pongs and the like carry nothing for us
+                    if !msg.is_text() {
+                        continue;
+                    }
+                    return parse_payload(msg.to_text().unwrap_or(""));
+                }
+                Some(Err(e)) => {
+                    self.socket = None;
+                    return Err(PodpingError::Disconnected(format!("{:#?}", e)));
+                }
+                None => {
+                    self.socket = None;
+                    return Err(PodpingError::Disconnected("Socket closed.".to_string()));
+                }
+            }
+        }
+    }
+}
+
+
+//Replay -----------------------------------------------------------------------------------------------------
+//##: Plays back podping socket messages saved one per line, from a file or from stdin when there's no path.
+//##: Useful for trying the live pipeline offline and for reproducing what happened in production
+pub struct PodpingReplay {
+    path: Option<PathBuf>,
+    lines: Option<Lines<BufReader<Box<dyn AsyncRead + Send + Unpin>>>>,
+    line_number: u64,
+}
+
+impl PodpingReplay {
+    pub fn new(path: Option<PathBuf>) -> PodpingReplay {
+        PodpingReplay {
+            path,
+            lines: None,
+            line_number: 0,
+        }
+    }
+}
+
+impl fmt::Debug for PodpingReplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PodpingReplay")
+            .field("path", &self.path)
+            .field("line_number", &self.line_number)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl PodpingSource for PodpingReplay {
+    //##: A replay only plays once, reconnecting after it's done doesn't start it over
+    async fn connect(&mut self) -> PodpingResult<()> {
+        if self.lines.is_some() || self.line_number > 0 {
+            return Err(PodpingError::Finished);
+        }
+
+        let reader: Box<dyn AsyncRead + Send + Unpin>;
+        match &self.path {
+            Some(path) => {
+                match tokio::fs::File::open(path).await {
+                    Ok(file) => {
+                        reader = Box::new(file);
+                    }
+                    Err(e) => {
+                        return Err(PodpingError::Disconnected(format!("Can't open replay file: [{}] {}", path.display(), e)));
+                    }
+                }
+            }
+            None => {
+                reader = Box::new(tokio::io::stdin());
+            }
+        }
+        self.lines = Some(BufReader::new(reader).lines());
+
+        return Ok(());
+    }
+
+    async fn next_payload(&mut self) -> PodpingResult<SocketPayload> {
+        let lines = match self.lines.as_mut() {
+            Some(open_lines) => {
+                open_lines
+            }
+            None => {
+                return Err(PodpingError::Finished);
+            }
+        };
+
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => {
+                    self.line_number += 1;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match parse_payload(&line) {
+                        Err(PodpingError::Unreadable(e)) => {
+                            return Err(PodpingError::Unreadable(format!("Line {}: {}", self.line_number, e)));
+                        }
+                        parsed => {
+                            return parsed;
+                        }
+                    }
+                }
+                Ok(None) => {
+                    return Err(PodpingError::Finished);
+                }
+                Err(e) => {
+                    return Err(PodpingError::Unreadable(format!("Line {}: {}", self.line_number + 1, e)));
+                }
+            }
+        }
+    }
+
+    //##: Recorded block numbers are from some other time, so they shouldn't move the live read position
+    fn resumable(&self) -> bool {
+        false
+    }
+}
+
+
+//Cursor -----------------------------------------------------------------------------------------------------
+//##: How far along the podping stream we've read.  Kept in the database so a dropped socket, or a restart, shows
+//##: up as a gap in the block numbers, and remembers recent podping ids so nothing is handled twice
+pub struct PodpingCursor {
+    last_block: u64,
+    saved_block: u64,
+    seen_ids: VecDeque<String>,
+    reconnected: bool,
+    database: Option<String>,
+}
+
+impl PodpingCursor {
+    pub fn new(database: Option<String>) -> PodpingCursor {
+        let mut last_block = 0;
+        if let Some(database_file) = &database {
+            last_block = dbif::get_state_value_from_db(database_file, PODPING_BLOCK_STATE.to_string()).unwrap_or(0);
+            println!("PODPING: Last block read: [{}]", last_block);
+        }
+
+        PodpingCursor {
+            last_block,
+            saved_block: last_block,
+            seen_ids: VecDeque::new(),
+            reconnected: true,
+            database,
+        }
+    }
+
+    pub fn reconnected(&mut self) {
+        self.reconnected = true;
+    }
+
+    //##: The podpings in this payload that haven't been handled yet
+    pub fn accept(&mut self, socket_payload: SocketPayload) -> Vec<PodpingPayload> {
+        //##: The socket only streams from the head of the chain, so what went by while we were away can't be
+        //##: asked for again.  Say how much was missed, new episodes are still picked up by the episode tracker
+        if self.reconnected {
+            self.reconnected = false;
+            if self.last_block > 0 && socket_payload.n > self.last_block + 1 {
+                let missed_blocks = socket_payload.n - self.last_block - 1;
+                eprintln!(
+                    "PODPING: Missed [{}] blocks, about [{}] seconds, from [{}] to [{}].",
+                    missed_blocks, missed_blocks * PODPING_BLOCK_SECONDS, self.last_block + 1, socket_payload.n - 1
+                );
+            }
+        }
+
+        //##: Blocks before the one we stopped at were already handled before a restart.  That block itself can
+        //##: come in over several messages, so it's handled again in full.  Past that, repeats are caught by their ids
+        if socket_payload.n < self.last_block && self.seen_ids.is_empty() {
+            return Vec::new();
+        }
+
+        let mut podpings = Vec::new();
+        for podping in socket_payload.p {
+            if self.seen_ids.contains(&podping.i) {
+                continue;
+            }
+            self.seen_ids.push_back(podping.i.clone());
+            if self.seen_ids.len() > PODPING_SEEN_IDS {
+                self.seen_ids.pop_front();
+            }
+            podpings.push(podping);
+        }
+
+        if socket_payload.n > self.last_block {
+            self.last_block = socket_payload.n;
+        }
+
+        podpings
+    }
+
+    //##: Remember where we are once the podpings from accept() have been dealt with, so a restart picks up from there
+    pub fn handled(&mut self) {
+        if self.last_block > self.saved_block {
+            self.saved_block = self.last_block;
+            if let Some(database_file) = &self.database {
+                let _ = dbif::update_state_value_in_db(database_file, PODPING_BLOCK_STATE.to_string(), self.last_block);
+            }
+        }
+    }
+}
+
+
+//Utilities --------------------------------------------------------------------------------------------------
+fn parse_payload(text: &str) -> PodpingResult<SocketPayload> {
+    match serde_json::from_str::<SocketPayload>(text) {
+        Ok(socket_payload) => {
+            Ok(socket_payload)
+        }
+        Err(e) => {
+            Err(PodpingError::Unreadable(format!("{}", e)))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> PodpingReplay {
+        PodpingReplay::new(Some(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/sample_data/podping/replay.ndjson"))))
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let mut source = replay();
+        assert!(!source.resumable());
+        source.connect().await.unwrap();
+
+        let socket_payload = source.next_payload().await.unwrap();
+        assert_eq!(socket_payload.n, 80000001);
+        assert_eq!(socket_payload.p[0].p.reason, "update");
+        assert_eq!(socket_payload.p[0].p.iris[0], "https://mp3s.nashownotes.com/pc20rss.xml");
+
+        //##: Blank lines are skipped, bad ones reported without ending the replay
+        assert!(matches!(source.next_payload().await, Err(PodpingError::Unreadable(_))));
+        let socket_payload = source.next_payload().await.unwrap();
+        assert_eq!(socket_payload.p[0].p.reason, "live");
+
+        assert!(matches!(source.next_payload().await, Err(PodpingError::Finished)));
+        assert!(matches!(source.connect().await, Err(PodpingError::Finished)));
+    }
+
+    #[tokio::test]
+    async fn test_cursor() {
+        let mut source = replay();
+        source.connect().await.unwrap();
+        let first_payload = source.next_payload().await.unwrap();
+
+        let mut cursor = PodpingCursor::new(None);
+        assert_eq!(cursor.accept(first_payload.clone()).len(), 1);
+
+        //##: The same podping again, after a reconnect, is only handled once
+        cursor.reconnected();
+        assert!(cursor.accept(first_payload).is_empty());
+        assert_eq!(cursor.last_block, 80000001);
+
+        let _ = source.next_payload().await;
+        let second_payload = source.next_payload().await.unwrap();
+        assert_eq!(cursor.accept(second_payload).len(), 1);
+        assert_eq!(cursor.last_block, 80000003);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_restart() {
+        let mut source = replay();
+        source.connect().await.unwrap();
+        let first_payload = source.next_payload().await.unwrap();
+        let _ = source.next_payload().await;
+        let second_payload = source.next_payload().await.unwrap();
+
+        //##: A restart keeps the last block but none of the seen ids.  The block it stopped at may not have
+        //##: been handled in full, so it's handled again
+        let mut cursor = PodpingCursor::new(None);
+        cursor.last_block = 80000001;
+        assert_eq!(cursor.accept(first_payload.clone()).len(), 1);
+        assert!(cursor.accept(first_payload).is_empty());
+        assert_eq!(cursor.last_block, 80000001);
+
+        assert_eq!(cursor.accept(second_payload.clone()).len(), 1);
+        assert!(cursor.accept(second_payload).is_empty());
+        assert_eq!(cursor.last_block, 80000003);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_saved_once_handled() {
+        let database_file = concat!(env!("CARGO_MANIFEST_DIR"), "/target/podping-cursor-test.db").to_string();
+        let _ = std::fs::create_dir_all(std::path::Path::new(&database_file).parent().unwrap());
+        let _ = std::fs::remove_file(&database_file);
+        dbif::create_database(&database_file).unwrap();
+
+        let mut source = replay();
+        source.connect().await.unwrap();
+        let first_payload = source.next_payload().await.unwrap();
+
+        //##: Nothing is saved until the podpings have been handled
+        let mut cursor = PodpingCursor::new(Some(database_file.clone()));
+        assert_eq!(cursor.accept(first_payload).len(), 1);
+        assert_eq!(PodpingCursor::new(Some(database_file.clone())).last_block, 0);
+
+        cursor.handled();
+        assert_eq!(PodpingCursor::new(Some(database_file.clone())).last_block, 80000001);
+    }
+}
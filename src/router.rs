@@ -41,7 +41,7 @@ impl Router {
     pub fn get(&mut self, path: &str, handler: Box<dyn Handler>) {
         self.method_map
             .entry(Method::GET)
-            .or_insert_with(InternalRouter::new)
+            .or_default()
             .add(path, handler)
     }
 
@@ -49,7 +49,7 @@ impl Router {
     pub fn post(&mut self, path: &str, handler: Box<dyn Handler>) {
         self.method_map
             .entry(Method::POST)
-            .or_insert_with(InternalRouter::new)
+            .or_default()
             .add(path, handler)
     }
 